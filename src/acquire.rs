@@ -78,6 +78,68 @@ pub enum Decimation {
     DEC_65536,
 }
 
+impl Decimation {
+    /**
+     * All decimations, from the fastest sampling rate.
+     */
+    pub const ALL: [Decimation; 6] = [
+        Decimation::DEC_1,
+        Decimation::DEC_8,
+        Decimation::DEC_64,
+        Decimation::DEC_1024,
+        Decimation::DEC_8192,
+        Decimation::DEC_65536,
+    ];
+
+    /**
+     * Decimation factor.
+     */
+    #[must_use]
+    pub fn factor(self) -> u32 {
+        match self {
+            Decimation::DEC_1 => 1,
+            Decimation::DEC_8 => 8,
+            Decimation::DEC_64 => 64,
+            Decimation::DEC_1024 => 1_024,
+            Decimation::DEC_8192 => 8_192,
+            Decimation::DEC_65536 => 65_536,
+        }
+    }
+
+    /**
+     * Duration of `samples` samples.
+     */
+    #[must_use]
+    pub fn duration(self, samples: u32) -> std::time::Duration {
+        std::time::Duration::from_nanos(u64::from(samples) * u64::from(self.factor()) * 8)
+    }
+
+    /**
+     * Number of samples during `duration`.
+     */
+    pub fn samples(self, duration: std::time::Duration) -> Result<u32, crate::Error> {
+        let samples = duration.as_nanos() / (u128::from(self.factor()) * 8);
+
+        u32::try_from(samples).map_err(|_| crate::Error::OutOfRange {
+            value: samples as f64,
+            min: 0.,
+            max: u32::MAX.into(),
+        })
+    }
+
+    /**
+     * Smallest decimation which buffer lasts at least `duration`, the
+     * highest one if none does.
+     */
+    #[must_use]
+    pub fn for_duration(duration: std::time::Duration) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|decimation| SamplingRate::from(*decimation).buffer_duration() >= duration)
+            .unwrap_or(Decimation::DEC_65536)
+    }
+}
+
 impl std::convert::From<Decimation> for String {
     fn from(decimation: Decimation) -> Self {
         let s = match decimation {
@@ -133,6 +195,14 @@ pub enum SamplingRate {
 }
 
 impl SamplingRate {
+    /**
+     * Sampling frequency in Hz.
+     */
+    #[must_use]
+    pub fn frequency(self) -> f64 {
+        125_000_000. / f64::from(Decimation::from(self).factor())
+    }
+
     #[must_use]
     pub fn buffer_duration(self) -> std::time::Duration {
         let (s, ns) = match self {
@@ -222,14 +292,14 @@ impl Acquire {
     /**
      * Starts acquisition.
      */
-    pub fn start(&mut self) {
+    pub fn start(&self) {
         self.socket.send("ACQ:START");
     }
 
     /**
      * Stops acquisition.
      */
-    pub fn stop(&mut self) {
+    pub fn stop(&self) {
         self.socket.send("ACQ:STOP");
     }
 
@@ -327,9 +397,51 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_sampling_rate_frequency() {
+        assert_eq!(
+            crate::acquire::SamplingRate::RATE_125MHz.frequency(),
+            125_000_000.
+        );
+        assert_eq!(
+            crate::acquire::SamplingRate::RATE_15_6MHz.frequency(),
+            15_625_000.
+        );
+    }
+
+    #[test]
+    fn test_decimation_duration() {
+        let decimation = crate::acquire::Decimation::DEC_64;
+
+        assert_eq!(
+            decimation.duration(100_000),
+            std::time::Duration::from_micros(51_200)
+        );
+        assert_eq!(
+            decimation.samples(std::time::Duration::from_micros(51_200)),
+            Ok(100_000)
+        );
+        assert_eq!(
+            crate::acquire::Decimation::DEC_1.samples(std::time::Duration::from_secs(100)),
+            Err(crate::Error::OutOfRange {
+                value: 12_500_000_000.,
+                min: 0.,
+                max: u32::MAX.into(),
+            })
+        );
+        assert_eq!(
+            crate::acquire::Decimation::for_duration(std::time::Duration::from_millis(5)),
+            crate::acquire::Decimation::DEC_64
+        );
+        assert_eq!(
+            crate::acquire::Decimation::for_duration(std::time::Duration::from_secs(60)),
+            crate::acquire::Decimation::DEC_65536
+        );
+    }
+
     #[test]
     fn test_status() {
-        let (rx, mut rp) = crate::test::create_client();
+        let (rx, rp) = crate::test::create_client();
 
         rp.acquire.start();
         assert_eq!("ACQ:START\r\n", rx.recv().unwrap());
@@ -0,0 +1,260 @@
+use crate::acquire::{Decimation, Gain, SamplingRate, Source};
+use crate::measure::Measure;
+use crate::trigger::TriggerConfig;
+use crate::waveform::Waveform;
+
+/**
+ * Highest peak voltage kept on the LV range, leaving a margin under ±1 V.
+ */
+const LV_LIMIT: f64 = 0.9;
+
+/**
+ * Minimum and maximum number of visible periods.
+ */
+const MIN_PERIODS: f64 = 2.;
+const MAX_PERIODS: f64 = 10.;
+
+/**
+ * Trigger hysteresis, relative to the peak to peak voltage.
+ */
+const HYSTERESIS: f64 = 0.05;
+
+/**
+ * Settings chosen by `Redpitaya::autoset`.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Autoset {
+    pub source: Source,
+    pub gain: Gain,
+    pub decimation: Decimation,
+    /**
+     * Edge trigger on the signal midpoint, `NOW` for a signal without edges.
+     */
+    pub trigger: TriggerConfig,
+    /**
+     * Signal frequency in Hz, `None` if no period has been found.
+     */
+    pub frequency: Option<f64>,
+    /**
+     * Duration to display around the trigger, 10 periods at most.
+     */
+    pub window: std::time::Duration,
+}
+
+impl Autoset {
+    /**
+     * Choose the settings from a `probe` captured with the HV gain.
+     */
+    pub fn new(
+        source: Source,
+        probe: &Waveform,
+        frequency: Option<f64>,
+    ) -> Result<Self, crate::Error> {
+        let channel = probe.channel(source).ok_or(crate::Error::Channel(source))?;
+        let measure = Measure::new(&channel.samples, probe.interval());
+        let (max, min) = (measure.max()?, measure.min()?);
+
+        let gain = if max.abs().max(min.abs()) < LV_LIMIT {
+            Gain::LV
+        } else {
+            Gain::HV
+        };
+
+        let decimation = match frequency {
+            Some(frequency) => Decimation::for_duration(std::time::Duration::from_secs_f64(
+                MIN_PERIODS / frequency,
+            )),
+            None => probe.decimation,
+        };
+
+        let buffer = SamplingRate::from(decimation).buffer_duration();
+        let window = match frequency {
+            Some(frequency) => {
+                buffer.min(std::time::Duration::from_secs_f64(MAX_PERIODS / frequency))
+            }
+            None => buffer,
+        };
+
+        let falling = match (measure.rise_time(), measure.fall_time()) {
+            (Ok(rise), Ok(fall)) => fall < rise,
+            _ => false,
+        };
+        let edge = match (frequency, source, falling) {
+            (None, _, _) => crate::trigger::Source::NOW,
+            (Some(_), Source::IN1, false) => crate::trigger::Source::CH1_PE,
+            (Some(_), Source::IN1, true) => crate::trigger::Source::CH1_NE,
+            (Some(_), Source::IN2, false) => crate::trigger::Source::CH2_PE,
+            (Some(_), Source::IN2, true) => crate::trigger::Source::CH2_NE,
+        };
+
+        Ok(Self {
+            source,
+            gain,
+            decimation,
+            trigger: TriggerConfig {
+                source: edge,
+                level: ((max + min) / 2.) as f32,
+                hysteresis: ((max - min) * HYSTERESIS) as f32,
+                delay: 0,
+                external_level: None,
+            },
+            frequency,
+            window,
+        })
+    }
+
+    /**
+     * Capture configuration using these settings.
+     */
+    #[must_use]
+    pub fn capture_config(&self) -> crate::capture::CaptureConfig {
+        crate::capture::CaptureConfig {
+            sources: vec![self.source],
+            decimation: self.decimation,
+            gain: Some(self.gain),
+            trigger: self.trigger.source,
+            level: Some(self.trigger.level),
+            ..Default::default()
+        }
+    }
+}
+
+impl crate::Redpitaya {
+    /**
+     * Probe `source` and apply gain, decimation and trigger settings to show
+     * 2 to 10 periods, like the auto button of a scope.
+     *
+     * The trigger is configured but not armed, see `Autoset::capture_config`.
+     */
+    pub fn autoset(&self, source: Source) -> Result<Autoset, crate::Error> {
+        self.acquire.set_gain(source, Gain::HV);
+
+        // Trigger margin, `capture` adds the buffer fill: up to 2.1 s here,
+        // the slowest rate isn’t probed as its buffer lasts 8.6 s
+        let (frequency, probe) = self.estimate(
+            source,
+            std::time::Duration::from_secs(1),
+            &Decimation::ALL[..Decimation::ALL.len() - 1],
+        )?;
+
+        let autoset = Autoset::new(source, &probe, frequency)?;
+
+        self.acquire.set_gain(source, autoset.gain);
+        self.acquire.set_decimation(autoset.decimation);
+        self.trigger.set_level(autoset.trigger.level);
+        self.trigger.set_hysteresis(autoset.trigger.hysteresis);
+        self.trigger.set_delay(autoset.trigger.delay);
+
+        Ok(autoset)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    /**
+     * Sawtooth at 1 kHz between `low` and `high`, sampled at 1.9 MHz:
+     * slow rising ramp, falling in 4 samples.
+     */
+    fn probe(low: f64, high: f64) -> crate::waveform::Waveform {
+        let period = 125e6 / 64. / 1e3;
+
+        let samples = (0..16_384)
+            .map(|n| {
+                let phase = (n as f64 % period) / period;
+                let x = if phase < 0.998 {
+                    phase / 0.998
+                } else {
+                    (1. - phase) / 0.002
+                };
+
+                low + (high - low) * x
+            })
+            .collect();
+
+        crate::waveform::Waveform {
+            channels: vec![crate::waveform::Channel {
+                gain: crate::acquire::Gain::HV,
+                ..crate::test::channel(crate::acquire::Source::IN2, samples)
+            }],
+            decimation: crate::acquire::Decimation::DEC_64,
+            trigger: 8_192,
+            ..crate::test::waveform(Vec::new())
+        }
+    }
+
+    #[test]
+    fn test_autoset() {
+        let autoset =
+            crate::autoset::Autoset::new(crate::acquire::Source::IN2, &probe(-0.5, 0.7), Some(1e3))
+                .unwrap();
+
+        assert_eq!(autoset.gain, crate::acquire::Gain::LV);
+        // 2 ms needed, 1 ms buffer at DEC_8
+        assert_eq!(autoset.decimation, crate::acquire::Decimation::DEC_64);
+        // 10 periods don’t fit in the 8.4 ms buffer
+        assert_eq!(
+            autoset.window,
+            crate::acquire::SamplingRate::RATE_1_9MHz.buffer_duration()
+        );
+        assert_eq!(autoset.trigger.source, crate::trigger::Source::CH2_NE);
+        assert!((autoset.trigger.level - 0.1).abs() < 1e-3);
+        assert!((autoset.trigger.hysteresis - 0.06).abs() < 1e-3);
+
+        let config = autoset.capture_config();
+        assert_eq!(config.sources, vec![crate::acquire::Source::IN2]);
+        assert_eq!(config.gain, Some(crate::acquire::Gain::LV));
+        assert_eq!(config.trigger, crate::trigger::Source::CH2_NE);
+    }
+
+    #[test]
+    fn test_autoset_hv() {
+        let autoset =
+            crate::autoset::Autoset::new(crate::acquire::Source::IN2, &probe(-5., 5.), Some(1e3))
+                .unwrap();
+
+        assert_eq!(autoset.gain, crate::acquire::Gain::HV);
+    }
+
+    #[test]
+    fn test_autoset_dc() {
+        let probe = crate::waveform::Waveform {
+            channels: vec![crate::waveform::Channel {
+                gain: crate::acquire::Gain::HV,
+                ..crate::test::channel(crate::acquire::Source::IN1, vec![3.; 100])
+            }],
+            ..probe(0., 1.)
+        };
+        let autoset =
+            crate::autoset::Autoset::new(crate::acquire::Source::IN1, &probe, None).unwrap();
+
+        assert_eq!(autoset.gain, crate::acquire::Gain::HV);
+        assert_eq!(
+            crate::autoset::Autoset::new(crate::acquire::Source::IN2, &probe, None).err(),
+            Some(crate::Error::Channel(crate::acquire::Source::IN2))
+        );
+        assert_eq!(autoset.decimation, crate::acquire::Decimation::DEC_64);
+        assert_eq!(autoset.trigger.source, crate::trigger::Source::NOW);
+        assert_eq!(autoset.trigger.level, 3.);
+    }
+
+    #[test]
+    #[cfg(feature = "mock")]
+    fn test_autoset_timeout() {
+        let (rx, rp) = crate::test::create_client();
+
+        assert_eq!(
+            rp.autoset(crate::acquire::Source::IN1),
+            Err(crate::Error::Timeout)
+        );
+        crate::test::assert_received(
+            &rx,
+            &[
+                "ACQ:SOUR1:GAIN HV",
+                "ACQ:DEC 1",
+                "ACQ:DATA:UNITS VOLTS",
+                "ACQ:START",
+                "ACQ:TRIG NOW",
+            ],
+        );
+    }
+}
@@ -0,0 +1,376 @@
+use crate::acquire::Decimation;
+use crate::spectrum::fft::{self, Complex};
+use crate::waveform::Waveform;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Scale {
+    LINEAR,
+    LOGARITHMIC,
+}
+
+impl Scale {
+    /**
+     * `steps` frequencies from `start` to `end`, both included.
+     */
+    #[must_use]
+    pub fn frequencies(self, start: f64, end: f64, steps: usize) -> Vec<f64> {
+        if steps < 2 {
+            return vec![start; steps];
+        }
+
+        (0..steps)
+            .map(|i| {
+                let x = i as f64 / (steps - 1) as f64;
+
+                match self {
+                    Scale::LINEAR => start + (end - start) * x,
+                    Scale::LOGARITHMIC => start * (end / start).powf(x),
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BodeConfig {
+    pub output: crate::generator::Source,
+    /**
+     * Input connected to the stimulus, at the DUT input.
+     */
+    pub reference: crate::acquire::Source,
+    /**
+     * Input connected to the DUT output.
+     */
+    pub response: crate::acquire::Source,
+    /**
+     * In Hz.
+     */
+    pub start: f64,
+    /**
+     * In Hz.
+     */
+    pub end: f64,
+    pub steps: usize,
+    pub scale: Scale,
+    /**
+     * Stimulus amplitude, in volts.
+     */
+    pub amplitude: f32,
+    /**
+     * Minimum number of periods in the buffer, used to pick the decimation.
+     */
+    pub periods: f64,
+    /**
+     * Wait after each frequency change.
+     */
+    pub settling: std::time::Duration,
+    /**
+     * Time allowed for the trigger at each frequency, on top of the buffer
+     * duration, see `CaptureConfig::timeout`.
+     */
+    pub timeout: std::time::Duration,
+}
+
+impl std::default::Default for BodeConfig {
+    fn default() -> Self {
+        Self {
+            output: crate::generator::Source::OUT1,
+            reference: crate::acquire::Source::IN1,
+            response: crate::acquire::Source::IN2,
+            start: 100.,
+            end: 1e6,
+            steps: 25,
+            scale: Scale::LOGARITHMIC,
+            amplitude: 0.5,
+            periods: 10.,
+            settling: std::time::Duration::from_millis(10),
+            timeout: std::time::Duration::from_secs(1),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Point {
+    /**
+     * In Hz.
+     */
+    pub frequency: f64,
+    /**
+     * Response over reference, in dB.
+     */
+    pub gain: f64,
+    /**
+     * Response relative to reference, in degrees between -180 and 180.
+     */
+    pub phase: f64,
+}
+
+impl Point {
+    /**
+     * Gain and phase at `frequency` Hz, see `ratio`.
+     */
+    pub fn new(
+        waveform: &Waveform,
+        reference: crate::acquire::Source,
+        response: crate::acquire::Source,
+        frequency: f64,
+    ) -> Result<Self, crate::Error> {
+        Ok(Self::from_ratio(
+            frequency,
+            ratio(waveform, reference, response, frequency)?,
+        ))
+    }
+
+    fn from_ratio(frequency: f64, ratio: Complex) -> Self {
+        Self {
+            frequency,
+            gain: 20. * ratio.norm().log10(),
+            phase: ratio.arg().to_degrees(),
+        }
+    }
+}
+
+/**
+ * Response over reference at `frequency` Hz, computed by single bin DFT on a
+ * whole number of periods.
+ */
+pub fn ratio(
+    waveform: &Waveform,
+    reference: crate::acquire::Source,
+    response: crate::acquire::Source,
+    frequency: f64,
+) -> Result<Complex, crate::Error> {
+    let samples = |source| {
+        let samples = waveform
+            .channel(source)
+            .ok_or(crate::Error::Channel(source))?
+            .samples
+            .as_slice();
+
+        if samples.is_empty() {
+            Err(crate::Error::Measure(crate::measure::Invalid::EMPTY))
+        } else {
+            Ok(samples)
+        }
+    };
+    let (reference, response) = (samples(reference)?, samples(response)?);
+
+    // Cycles per sample
+    let frequency = frequency * waveform.interval();
+    let len = reference.len().min(response.len());
+    let periods = (len as f64 * frequency).floor();
+    let len = if periods >= 1. {
+        ((periods / frequency).round() as usize).min(len)
+    } else {
+        len
+    };
+
+    let reference = fft::single_bin(&reference[..len], frequency);
+    let response = fft::single_bin(&response[..len], frequency);
+
+    if reference.norm() == 0. {
+        return Err(crate::measure::Invalid::FLAT.into());
+    }
+
+    Ok(response / reference)
+}
+
+/**
+ * Frequency response table.
+ */
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bode {
+    pub points: Vec<Point>,
+}
+
+impl Bode {
+    /**
+     * Write `frequency,gain,phase` lines, with a header.
+     */
+    pub fn write_csv<W: std::io::Write>(&self, mut writer: W) -> Result<(), crate::Error> {
+        writeln!(writer, "frequency,gain,phase")?;
+
+        for point in &self.points {
+            writeln!(writer, "{},{},{}", point.frequency, point.gain, point.phase)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl crate::Redpitaya {
+    /**
+     * Sweep a sine on `config.output` and measure the response at each
+     * frequency.
+     *
+     * The output is stopped afterwards.
+     */
+    pub fn bode(&self, config: &BodeConfig) -> Result<Bode, crate::Error> {
+        let points = self
+            .frequency_response(config)?
+            .into_iter()
+            .map(|(frequency, ratio)| Point::from_ratio(frequency, ratio))
+            .collect();
+
+        Ok(Bode { points })
+    }
+
+    /**
+     * Response over reference ratio at each frequency of the sweep.
+     */
+    pub(crate) fn frequency_response(
+        &self,
+        config: &BodeConfig,
+    ) -> Result<Vec<(f64, Complex)>, crate::Error> {
+        let output = config.output;
+
+        self.generator.set_sine(
+            output,
+            config.start.round().max(1.) as u32,
+            config.amplitude,
+        )?;
+        self.generator.start(output);
+
+        let response = self.sweep(config);
+
+        self.generator.stop(output);
+
+        response
+    }
+
+    fn sweep(&self, config: &BodeConfig) -> Result<Vec<(f64, Complex)>, crate::Error> {
+        let mut response = Vec::new();
+
+        for frequency in config
+            .scale
+            .frequencies(config.start, config.end, config.steps)
+        {
+            let frequency = frequency.round().max(1.);
+
+            self.generator
+                .set_frequency(config.output, frequency as u32);
+            std::thread::sleep(config.settling);
+
+            let duration = std::time::Duration::from_secs_f64(config.periods / frequency);
+            let capture = crate::capture::CaptureConfig {
+                sources: vec![config.reference, config.response],
+                decimation: Decimation::for_duration(duration),
+                timeout: config.timeout,
+                ..Default::default()
+            };
+            let waveform = self.capture(&capture)?;
+
+            response.push((
+                frequency,
+                ratio(&waveform, config.reference, config.response, frequency)?,
+            ));
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn test_frequencies() {
+        assert_eq!(
+            crate::bode::Scale::LINEAR.frequencies(10., 40., 4),
+            vec![10., 20., 30., 40.]
+        );
+
+        let frequencies = crate::bode::Scale::LOGARITHMIC.frequencies(10., 1_000., 3);
+        assert_eq!(frequencies.len(), 3);
+        assert!((frequencies[1] - 100.).abs() < 1e-9);
+        assert_eq!(frequencies[2], 1_000.);
+    }
+
+    #[test]
+    fn test_point() {
+        // 1 MHz at 15.6 MHz, 32.6 periods
+        let sine = |amplitude, phase| crate::test::sine(amplitude, 1e6, phase, 15.625e6, 510);
+        let waveform = crate::waveform::Waveform {
+            channels: vec![
+                crate::test::channel(crate::acquire::Source::IN1, sine(1., 0.)),
+                crate::test::channel(
+                    crate::acquire::Source::IN2,
+                    sine(0.5, -std::f64::consts::FRAC_PI_4),
+                ),
+            ],
+            decimation: crate::acquire::Decimation::DEC_8,
+            ..crate::test::waveform(Vec::new())
+        };
+
+        let point = crate::bode::Point::new(
+            &waveform,
+            crate::acquire::Source::IN1,
+            crate::acquire::Source::IN2,
+            1e6,
+        )
+        .unwrap();
+        assert!((point.frequency - 1e6).abs() < 1e-3);
+        assert!((point.gain + 6.0206).abs() < 1e-2);
+        assert!((point.phase + 45.).abs() < 1e-2);
+
+        assert_eq!(
+            crate::bode::Point::new(
+                &crate::test::waveform(sine(1., 0.)),
+                crate::acquire::Source::IN1,
+                crate::acquire::Source::IN2,
+                1e6,
+            ),
+            Err(crate::Error::Channel(crate::acquire::Source::IN2))
+        );
+    }
+
+    #[test]
+    fn test_write_csv() {
+        let bode = crate::bode::Bode {
+            points: vec![crate::bode::Point {
+                frequency: 100.,
+                gain: -3.,
+                phase: -45.,
+            }],
+        };
+
+        let mut csv = Vec::new();
+        bode.write_csv(&mut csv).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "frequency,gain,phase\n100,-3,-45\n"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "mock")]
+    fn test_bode() {
+        let (rx, rp) = crate::test::create_client();
+
+        let config = crate::bode::BodeConfig {
+            start: 10_000.,
+            timeout: std::time::Duration::from_millis(10),
+            ..Default::default()
+        };
+
+        assert_eq!(rp.bode(&config), Err(crate::Error::Timeout));
+        crate::test::assert_received(
+            &rx,
+            &[
+                "SOUR1:FUNC SINE",
+                "SOUR1:FREQ:FIX 10000",
+                "SOUR1:VOLT:OFFS 0",
+                "SOUR1:VOLT 0.5",
+                "OUTPUT1:STATE ON",
+                "SOUR1:FREQ:FIX 10000",
+                "ACQ:DEC 8",
+                "ACQ:DATA:UNITS VOLTS",
+                "ACQ:START",
+                "ACQ:TRIG NOW",
+                "OUTPUT1:STATE OFF",
+            ],
+        );
+    }
+}
@@ -17,7 +17,17 @@ impl std::convert::From<Source> for String {
     }
 }
 
+impl std::convert::From<crate::generator::Source> for Source {
+    fn from(source: crate::generator::Source) -> Self {
+        match source {
+            crate::generator::Source::OUT1 => Source::OUT1,
+            crate::generator::Source::OUT2 => Source::OUT2,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Mode {
     CONTINUOUS,
     BURST,
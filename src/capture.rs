@@ -0,0 +1,380 @@
+use crate::acquire::{Decimation, Gain, Source};
+use crate::data::Unit;
+use crate::waveform::Waveform;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CaptureConfig {
+    pub sources: Vec<Source>,
+    pub decimation: Decimation,
+    /**
+     * Gain of all sources, keep the current one if `None`.
+     */
+    pub gain: Option<Gain>,
+    pub units: Unit,
+    pub trigger: crate::trigger::Source,
+    /**
+     * Trigger level in volts, keep the current one if `None`.
+     */
+    pub level: Option<f32>,
+    /**
+     * Time allowed for the trigger, on top of the buffer duration at
+     * `decimation` needed to fill the buffer, see `CaptureConfig::wait`.
+     */
+    pub timeout: std::time::Duration,
+}
+
+impl std::default::Default for CaptureConfig {
+    fn default() -> Self {
+        Self {
+            sources: vec![Source::IN1, Source::IN2],
+            decimation: Decimation::DEC_1,
+            gain: None,
+            units: Unit::VOLTS,
+            trigger: crate::trigger::Source::NOW,
+            level: None,
+            timeout: std::time::Duration::from_secs(1),
+        }
+    }
+}
+
+impl CaptureConfig {
+    /**
+     * Longest wait for a triggered and filled buffer: its duration plus
+     * `timeout`.
+     */
+    #[must_use]
+    pub fn wait(&self) -> std::time::Duration {
+        crate::acquire::SamplingRate::from(self.decimation).buffer_duration() + self.timeout
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SegmentConfig {
+    pub capture: CaptureConfig,
+    /**
+     * Number of records.
+     */
+    pub count: usize,
+    /**
+     * Samples kept before the trigger.
+     */
+    pub pre_trigger: u32,
+    /**
+     * Samples kept after the trigger, including the trigger one.
+     */
+    pub post_trigger: u32,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Segments {
+    pub records: Vec<Waveform>,
+    /**
+     * Time between a trigger and the next re-arm, the readout and the
+     * pre-trigger fill included.
+     */
+    pub dead_times: Vec<std::time::Duration>,
+}
+
+impl Segments {
+    #[must_use]
+    pub fn min_dead_time(&self) -> Option<std::time::Duration> {
+        self.dead_times.iter().min().copied()
+    }
+
+    #[must_use]
+    pub fn max_dead_time(&self) -> Option<std::time::Duration> {
+        self.dead_times.iter().max().copied()
+    }
+
+    #[must_use]
+    pub fn mean_dead_time(&self) -> Option<std::time::Duration> {
+        let total = self.dead_times.iter().sum::<std::time::Duration>();
+
+        (!self.dead_times.is_empty()).then(|| total / self.dead_times.len() as u32)
+    }
+}
+
+impl crate::Redpitaya {
+    /**
+     * Single-shot acquisition: configure, arm, wait for the trigger and read
+     * the full buffer of every requested source.
+     */
+    pub fn capture(&self, config: &CaptureConfig) -> Result<Waveform, crate::Error> {
+        self.setup(config);
+        self.arm(
+            config,
+            crate::acquire::SamplingRate::from(config.decimation).buffer_duration() / 2,
+        );
+
+        match self.trigger.wait(config.wait())? {
+            crate::trigger::Outcome::TRIGGERED => self.fetch(config),
+            crate::trigger::Outcome::TIMEOUT => Err(crate::Error::Timeout),
+        }
+    }
+
+    /**
+     * Segmented acquisition: capture `config.count` triggered records in a
+     * row, re-arming after each readout.
+     *
+     * Stops at the first timeout with the records captured so far. The
+     * trigger delay is restored afterwards.
+     */
+    pub fn capture_segments(&self, config: &SegmentConfig) -> Result<Segments, crate::Error> {
+        let size = self.data.buffer_size()?;
+        let len = config.pre_trigger + config.post_trigger;
+
+        if len > size {
+            return Err(crate::Error::OutOfRange {
+                value: len.into(),
+                min: 0.,
+                max: size.into(),
+            });
+        }
+
+        let delay = self.trigger.delay()?;
+
+        self.setup(&config.capture);
+        // With a null delay, the trigger is in the middle of the buffer
+        self.trigger
+            .set_delay(config.post_trigger as i32 - size as i32 / 2);
+
+        let segments = self.segments(config);
+        self.trigger.set_delay(delay);
+
+        segments
+    }
+
+    fn segments(&self, config: &SegmentConfig) -> Result<Segments, crate::Error> {
+        let capture = &config.capture;
+        let mut segments = Segments::default();
+        let mut triggered = None;
+
+        for _ in 0..config.count {
+            self.arm(capture, capture.decimation.duration(config.pre_trigger));
+
+            if let Some(triggered) = triggered {
+                segments
+                    .dead_times
+                    .push(std::time::Instant::now() - triggered);
+            }
+
+            // The record is complete `post_trigger` samples after the trigger
+            let wait = capture.decimation.duration(config.post_trigger) + capture.timeout;
+
+            if self.trigger.wait(wait)? == crate::trigger::Outcome::TIMEOUT {
+                log::warn!(
+                    "Timeout after {} of {} segments",
+                    segments.records.len(),
+                    config.count
+                );
+                break;
+            }
+
+            triggered = Some(std::time::Instant::now());
+
+            segments.records.push(self.data.read_record(
+                &capture.sources,
+                config.pre_trigger,
+                config.post_trigger,
+            )?);
+        }
+
+        Ok(segments)
+    }
+
+    fn setup(&self, config: &CaptureConfig) {
+        self.acquire.set_decimation(config.decimation);
+        self.data.set_units(config.units);
+
+        if let Some(gain) = config.gain {
+            for source in &config.sources {
+                self.acquire.set_gain(*source, gain);
+            }
+        }
+
+        if let Some(level) = config.level {
+            self.trigger.set_level(level);
+        }
+    }
+
+    /**
+     * Start the acquisition and enable the trigger once the pre-trigger part
+     * of the buffer is filled.
+     */
+    fn arm(&self, config: &CaptureConfig, pre_trigger: std::time::Duration) {
+        self.acquire.start();
+        std::thread::sleep(pre_trigger);
+        self.trigger.enable(config.trigger);
+    }
+
+    /**
+     * Read the full buffer, units and gain are the ones of `config` when set.
+     */
+    fn fetch(&self, config: &CaptureConfig) -> Result<Waveform, crate::Error> {
+        let mut waveform = self.data.read_channels(&config.sources, ..)?;
+
+        waveform.units = config.units;
+        if let Some(gain) = config.gain {
+            for channel in &mut waveform.channels {
+                channel.gain = gain;
+            }
+        }
+
+        Ok(waveform)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    #[cfg(feature = "mock")]
+    fn test_capture_timeout() {
+        let (rx, rp) = crate::test::create_client();
+
+        let config = crate::capture::CaptureConfig {
+            sources: vec![crate::acquire::Source::IN1],
+            gain: Some(crate::acquire::Gain::LV),
+            level: Some(0.1),
+            timeout: std::time::Duration::from_millis(10),
+            ..Default::default()
+        };
+
+        assert_eq!(rp.capture(&config), Err(crate::Error::Timeout));
+        crate::test::assert_received(
+            &rx,
+            &[
+                "ACQ:DEC 1",
+                "ACQ:DATA:UNITS VOLTS",
+                "ACQ:SOUR1:GAIN LV",
+                "ACQ:TRIG:LEV 0.1",
+                "ACQ:START",
+                "ACQ:TRIG NOW",
+            ],
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "mock")]
+    fn test_capture_segments() {
+        let (rx, rp) = crate::test::create_client();
+
+        let mut config = crate::capture::SegmentConfig {
+            capture: crate::capture::CaptureConfig {
+                sources: vec![crate::acquire::Source::IN1],
+                timeout: std::time::Duration::from_millis(10),
+                ..Default::default()
+            },
+            count: 3,
+            pre_trigger: 10_000,
+            post_trigger: 10_000,
+        };
+
+        assert_eq!(
+            rp.capture_segments(&config),
+            Err(crate::Error::OutOfRange {
+                value: 20_000.,
+                min: 0.,
+                max: 16_384.,
+            })
+        );
+
+        config.post_trigger = 6_000;
+        assert_eq!(
+            rp.capture_segments(&config),
+            Ok(crate::capture::Segments::default())
+        );
+        crate::test::assert_received(
+            &rx,
+            &[
+                "ACQ:DEC 1",
+                "ACQ:DATA:UNITS VOLTS",
+                "ACQ:TRIG:DLY -2192",
+                "ACQ:START",
+                "ACQ:TRIG NOW",
+                "ACQ:TRIG:DLY 2314",
+            ],
+        );
+
+        // The mock external trigger always fires
+        config.capture.trigger = crate::trigger::Source::EXT_PE;
+        config.pre_trigger = 2;
+        config.post_trigger = 1;
+        config.count = 2;
+
+        let segments = rp.capture_segments(&config).unwrap();
+        assert_eq!(segments.records.len(), 2);
+        assert_eq!(segments.dead_times.len(), 1);
+        assert_eq!(segments.records[1].trigger, 2);
+        assert_eq!(
+            segments.records[1].channels[0].samples,
+            vec![1.2, 3.2, -1.2]
+        );
+    }
+
+    #[test]
+    fn test_wait() {
+        // 8.59 s to fill the buffer at the slowest rate
+        let config = crate::capture::CaptureConfig {
+            decimation: crate::acquire::Decimation::DEC_65536,
+            ..Default::default()
+        };
+
+        assert_eq!(config.wait(), std::time::Duration::new(9, 590_000_000));
+    }
+
+    #[test]
+    fn test_dead_times() {
+        let segments = crate::capture::Segments {
+            records: Vec::new(),
+            dead_times: vec![
+                std::time::Duration::from_millis(10),
+                std::time::Duration::from_millis(30),
+            ],
+        };
+
+        assert_eq!(
+            segments.min_dead_time(),
+            Some(std::time::Duration::from_millis(10))
+        );
+        assert_eq!(
+            segments.max_dead_time(),
+            Some(std::time::Duration::from_millis(30))
+        );
+        assert_eq!(
+            segments.mean_dead_time(),
+            Some(std::time::Duration::from_millis(20))
+        );
+        assert_eq!(crate::capture::Segments::default().mean_dead_time(), None);
+    }
+
+    #[test]
+    #[cfg(feature = "mock")]
+    fn test_fetch() {
+        let (_, rp) = crate::test::create_client();
+
+        let config = crate::capture::CaptureConfig {
+            sources: vec![crate::acquire::Source::IN1],
+            ..Default::default()
+        };
+
+        // The mock reports RAW units
+        let waveform = rp.fetch(&config).unwrap();
+        assert_eq!(waveform.trigger, 15_872);
+        assert_eq!(waveform.units, crate::data::Unit::VOLTS);
+        assert_eq!(waveform.channels.len(), 1);
+        assert_eq!(waveform.channels[0].gain, crate::acquire::Gain::HV);
+
+        let config = crate::capture::CaptureConfig {
+            gain: Some(crate::acquire::Gain::LV),
+            ..config
+        };
+        let waveform = rp.fetch(&config).unwrap();
+        assert_eq!(waveform.channels[0].gain, crate::acquire::Gain::LV);
+        assert_eq!(waveform.channels[0].samples.len(), 16_384);
+        assert_eq!(waveform.channels[0].samples[0], 0.5);
+        assert_eq!(
+            waveform.sampling_rate(),
+            crate::acquire::SamplingRate::RATE_125MHz
+        );
+    }
+}
@@ -0,0 +1,179 @@
+use crate::acquire::{Decimation, SamplingRate, Source};
+use crate::data::Data;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Block {
+    pub samples: Vec<f64>,
+    /**
+     * Number of samples overwritten before being read since the previous
+     * block.
+     */
+    pub lost: usize,
+    /**
+     * Host time when the write pointer has been read.
+     */
+    pub timestamp: std::time::SystemTime,
+}
+
+/**
+ * Follow the acquisition ring buffer write pointer to read new samples as
+ * they come.
+ *
+ * Each iteration waits a quarter of the buffer duration, then reads the
+ * samples written since the previous iteration.
+ */
+#[derive(Clone, Debug)]
+pub struct ContinuousCapture {
+    data: Data,
+    source: Source,
+    rate: SamplingRate,
+    size: u32,
+    pointer: u32,
+    last: std::time::Instant,
+}
+
+impl ContinuousCapture {
+    /**
+     * Start following the buffer, acquisition must already be running.
+     */
+    pub fn new(data: Data, source: Source, decimation: Decimation) -> Result<Self, crate::Error> {
+        let size = data.buffer_size()?;
+        let pointer = data.write_pointer()?;
+
+        Ok(Self {
+            data,
+            source,
+            rate: decimation.into(),
+            size,
+            pointer,
+            last: std::time::Instant::now(),
+        })
+    }
+
+    fn read(&mut self) -> Result<Block, crate::Error> {
+        let period = self.rate.buffer_duration() / 4;
+        if let Some(wait) = period.checked_sub(self.last.elapsed()) {
+            std::thread::sleep(wait);
+        }
+
+        let write_pointer = self.data.write_pointer()?;
+        let now = std::time::Instant::now();
+        let timestamp = std::time::SystemTime::now();
+        let expected = (now - self.last).as_secs_f64() * self.rate.frequency();
+
+        let mut samples = Vec::new();
+        for (start, end) in regions(self.pointer, write_pointer, self.size) {
+            samples.append(
+                &mut self
+                    .data
+                    .read_slice(self.source, start as u16, end as u16)?,
+            );
+        }
+
+        let lost = lost(samples.len(), expected, self.size);
+        if lost > 0 {
+            log::warn!("Overrun: {lost} samples lost");
+        }
+
+        self.pointer = write_pointer;
+        self.last = now;
+
+        Ok(Block {
+            samples,
+            lost,
+            timestamp,
+        })
+    }
+}
+
+impl Iterator for ContinuousCapture {
+    type Item = Result<Block, crate::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.read())
+    }
+}
+
+impl crate::Redpitaya {
+    /**
+     * Start a free running acquisition and follow it.
+     */
+    pub fn continuous(
+        &self,
+        source: Source,
+        decimation: Decimation,
+    ) -> Result<ContinuousCapture, crate::Error> {
+        self.acquire.set_decimation(decimation);
+        self.acquire.start();
+        self.trigger.disable();
+
+        ContinuousCapture::new(self.data.clone(), source, decimation)
+    }
+}
+
+/**
+ * Inclusive ranges written between `pointer` and `write_pointer`.
+ */
+fn regions(pointer: u32, write_pointer: u32, size: u32) -> Vec<(u32, u32)> {
+    match write_pointer.cmp(&pointer) {
+        std::cmp::Ordering::Equal => Vec::new(),
+        std::cmp::Ordering::Greater => vec![(pointer, write_pointer - 1)],
+        std::cmp::Ordering::Less if write_pointer == 0 => vec![(pointer, size - 1)],
+        std::cmp::Ordering::Less => vec![(pointer, size - 1), (0, write_pointer - 1)],
+    }
+}
+
+/**
+ * The write pointer only tells the position modulo the buffer size, use the
+ * elapsed time to count the number of times the buffer wrapped.
+ */
+fn lost(read: usize, expected: f64, size: u32) -> usize {
+    let wraps = ((expected - read as f64) / f64::from(size)).round();
+
+    if wraps > 0. {
+        wraps as usize * size as usize
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn test_regions() {
+        assert_eq!(crate::continuous::regions(10, 10, 16_384), vec![]);
+        assert_eq!(crate::continuous::regions(10, 20, 16_384), vec![(10, 19)]);
+        assert_eq!(
+            crate::continuous::regions(16_000, 20, 16_384),
+            vec![(16_000, 16_383), (0, 19)]
+        );
+        assert_eq!(
+            crate::continuous::regions(16_000, 0, 16_384),
+            vec![(16_000, 16_383)]
+        );
+    }
+
+    #[test]
+    fn test_lost() {
+        assert_eq!(crate::continuous::lost(4_000, 4_010., 16_384), 0);
+        assert_eq!(crate::continuous::lost(4_000, 20_390., 16_384), 16_384);
+        assert_eq!(crate::continuous::lost(0, 3., 16_384), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "mock")]
+    fn test_continuous() {
+        let (rx, rp) = crate::test::create_client();
+
+        let mut capture = rp
+            .continuous(
+                crate::acquire::Source::IN1,
+                crate::acquire::Decimation::DEC_1,
+            )
+            .unwrap();
+        crate::test::assert_received(&rx, &["ACQ:DEC 1", "ACQ:START", "ACQ:TRIG DISABLED"]);
+
+        let block = capture.next().unwrap().unwrap();
+        assert!(block.samples.is_empty());
+    }
+}
@@ -0,0 +1,256 @@
+use crate::acquire::{Decimation, SamplingRate, Source};
+use crate::measure::{Invalid, Measure};
+use crate::waveform::Waveform;
+
+/**
+ * Minimum number of samples per period when choosing the decimation.
+ */
+const SAMPLES_PER_PERIOD: f64 = 10.;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CounterConfig {
+    pub source: Source,
+    /**
+     * Minimum measurement time, captures are combined until reaching it.
+     */
+    pub gate: std::time::Duration,
+    /**
+     * Timeout of each capture.
+     */
+    pub timeout: std::time::Duration,
+}
+
+impl std::default::Default for CounterConfig {
+    fn default() -> Self {
+        Self {
+            source: Source::IN1,
+            gate: std::time::Duration::from_secs(1),
+            timeout: std::time::Duration::from_secs(1),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Count {
+    /**
+     * In Hz.
+     */
+    pub frequency: f64,
+    /**
+     * In seconds.
+     */
+    pub period: f64,
+    /**
+     * Standard deviation of single periods, in seconds.
+     */
+    pub deviation: f64,
+    /**
+     * Sum of the time between the first and the last counted edge of each
+     * capture.
+     */
+    pub gate: std::time::Duration,
+    pub captures: usize,
+}
+
+impl Count {
+    /**
+     * Reciprocal count: whole periods between interpolated rising crossings,
+     * divided by the time they last.
+     */
+    pub fn new(waveforms: &[Waveform], source: Source) -> Result<Self, crate::Error> {
+        let mut periods = Vec::new();
+        let mut time = 0.;
+
+        for waveform in waveforms {
+            let channel = waveform
+                .channel(source)
+                .ok_or(crate::Error::Channel(source))?;
+            let interval = waveform.interval();
+            let edges = Measure::new(&channel.samples, interval).rising_edges()?;
+
+            if let (Some(first), Some(last)) = (edges.first(), edges.last()) {
+                time += (last - first) * interval;
+            }
+
+            periods.extend(edges.windows(2).map(|w| (w[1] - w[0]) * interval));
+        }
+
+        if periods.is_empty() {
+            return Err(Invalid::EDGES.into());
+        }
+
+        let n = periods.len() as f64;
+        let period = time / n;
+        let deviation = if periods.len() > 1 {
+            (periods.iter().map(|p| (p - period).powi(2)).sum::<f64>() / (n - 1.)).sqrt()
+        } else {
+            0.
+        };
+
+        Ok(Self {
+            frequency: 1. / period,
+            period,
+            deviation,
+            gate: std::time::Duration::from_secs_f64(time),
+            captures: waveforms.len(),
+        })
+    }
+}
+
+/**
+ * Slowest sampling rate keeping `SAMPLES_PER_PERIOD` samples per period, to
+ * get the longest gate per capture.
+ */
+fn decimation(frequency: f64) -> Decimation {
+    Decimation::ALL
+        .into_iter()
+        .rev()
+        .find(|decimation| {
+            SamplingRate::from(*decimation).frequency() >= SAMPLES_PER_PERIOD * frequency
+        })
+        .unwrap_or(Decimation::DEC_1)
+}
+
+impl crate::Redpitaya {
+    /**
+     * Measure the frequency on `config.source`.
+     *
+     * A coarse estimate is made with captures at increasing decimation
+     * until enough edges are found, then the decimation is chosen from it.
+     */
+    pub fn counter(&self, config: &CounterConfig) -> Result<Count, crate::Error> {
+        let (frequency, _) = self.estimate(config.source, config.timeout, &Decimation::ALL)?;
+        let decimation = decimation(frequency.ok_or(Invalid::EDGES)?);
+        let mut waveforms = Vec::new();
+
+        loop {
+            waveforms.push(self.capture(&capture(config.source, config.timeout, decimation))?);
+
+            let count = Count::new(&waveforms, config.source)?;
+            if count.gate >= config.gate {
+                return Ok(count);
+            }
+        }
+    }
+
+    /**
+     * Capture at each of `decimations` until finding enough edges to
+     * measure a frequency. Returns it, if any, with the last capture.
+     */
+    pub(crate) fn estimate(
+        &self,
+        source: Source,
+        timeout: std::time::Duration,
+        decimations: &[Decimation],
+    ) -> Result<(Option<f64>, Waveform), crate::Error> {
+        let mut last = None;
+
+        for decimation in decimations {
+            let waveform = self.capture(&capture(source, timeout, *decimation))?;
+            let channel = waveform
+                .channel(source)
+                .ok_or(crate::Error::Channel(source))?;
+
+            if let Ok(frequency) = Measure::new(&channel.samples, waveform.interval()).frequency() {
+                return Ok((Some(frequency), waveform));
+            }
+
+            last = Some(waveform);
+        }
+
+        last.map(|waveform| (None, waveform))
+            .ok_or(Invalid::EMPTY.into())
+    }
+}
+
+fn capture(
+    source: Source,
+    timeout: std::time::Duration,
+    decimation: Decimation,
+) -> crate::capture::CaptureConfig {
+    crate::capture::CaptureConfig {
+        sources: vec![source],
+        decimation,
+        timeout,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    fn waveform(frequency: f64, phase: f64) -> crate::waveform::Waveform {
+        let samples = crate::test::sine(1., frequency, phase, 125e6 / 64., 16_384);
+
+        crate::waveform::Waveform {
+            channels: vec![crate::test::channel(crate::acquire::Source::IN2, samples)],
+            decimation: crate::acquire::Decimation::DEC_64,
+            ..crate::test::waveform(Vec::new())
+        }
+    }
+
+    #[test]
+    fn test_count() {
+        let waveforms = [waveform(12_345.678, 0.), waveform(12_345.678, 1.)];
+        let count = crate::counter::Count::new(&waveforms, crate::acquire::Source::IN2).unwrap();
+
+        assert!((count.frequency - 12_345.678).abs() < 1e-3);
+        assert!((count.period * count.frequency - 1.).abs() < 1e-12);
+        assert!(count.deviation < 1e-9);
+        assert_eq!(count.captures, 2);
+        // Two buffers of 8.39 ms, less two periods of 81 µs at most each
+        assert!(count.gate > std::time::Duration::from_micros(16_450));
+        assert!(count.gate < std::time::Duration::from_micros(16_800));
+    }
+
+    #[test]
+    fn test_count_invalid() {
+        let waveforms = [waveform(0., 0.)];
+
+        assert_eq!(
+            crate::counter::Count::new(&waveforms, crate::acquire::Source::IN2),
+            Err(crate::Error::Measure(crate::measure::Invalid::FLAT))
+        );
+        assert_eq!(
+            crate::counter::Count::new(&waveforms, crate::acquire::Source::IN1),
+            Err(crate::Error::Channel(crate::acquire::Source::IN1))
+        );
+    }
+
+    #[test]
+    fn test_decimation() {
+        assert_eq!(
+            crate::counter::decimation(1_000.),
+            crate::acquire::Decimation::DEC_8192
+        );
+        assert_eq!(
+            crate::counter::decimation(1e6),
+            crate::acquire::Decimation::DEC_8
+        );
+        assert_eq!(
+            crate::counter::decimation(50e6),
+            crate::acquire::Decimation::DEC_1
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "mock")]
+    fn test_counter() {
+        let (rx, rp) = crate::test::create_client();
+
+        let config = crate::counter::CounterConfig {
+            timeout: std::time::Duration::from_millis(10),
+            ..Default::default()
+        };
+
+        assert_eq!(rp.counter(&config), Err(crate::Error::Timeout));
+        crate::test::assert_received(
+            &rx,
+            &[
+                "ACQ:DEC 1",
+                "ACQ:DATA:UNITS VOLTS",
+                "ACQ:START",
+                "ACQ:TRIG NOW",
+            ],
+        );
+    }
+}
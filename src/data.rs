@@ -100,9 +100,17 @@ impl Data {
      *
      * start = {0,1,...,16384}
      * stop_pos = {0,1,...16384}
+     *
+     * The slice wraps around the end of the buffer if `start > end`, the
+     * buffer size is read first to check the number of samples.
      */
-    #[must_use]
-    pub fn read_slice(&self, source: crate::acquire::Source, start: u16, end: u16) -> Vec<f64> {
+    pub fn read_slice(
+        &self,
+        source: crate::acquire::Source,
+        start: u16,
+        end: u16,
+    ) -> Result<Vec<f64>, crate::Error> {
+        let size = self.buffer_size()? as usize;
         let data = self
             .socket
             .send(format!(
@@ -110,15 +118,20 @@ impl Data {
                 Into::<String>::into(source),
             ))
             .unwrap();
+        let len = (usize::from(end) + size - usize::from(start)) % size + 1;
 
-        Self::parse(&data)
+        Self::parse(&data, Some(len))
     }
 
     /**
      * Read `m` samples from start position on.
      */
-    #[must_use]
-    pub fn read(&self, source: crate::acquire::Source, start: u16, len: u32) -> Vec<f64> {
+    pub fn read(
+        &self,
+        source: crate::acquire::Source,
+        start: u16,
+        len: u32,
+    ) -> Result<Vec<f64>, crate::Error> {
         let data = self
             .socket
             .send(format!(
@@ -127,7 +140,7 @@ impl Data {
             ))
             .unwrap();
 
-        Self::parse(&data)
+        Self::parse(&data, Some(len as usize))
     }
 
     /**
@@ -137,28 +150,38 @@ impl Data {
      * trigger delay). Trigger delay by default is set to zero (in samples or
      * in seconds). If trigger delay is set to zero it will read full buf.
      * Size starting from trigger.
+     *
+     * The buffer size is read first to check the number of samples.
      */
-    #[must_use]
-    pub fn read_all(&self, source: crate::acquire::Source) -> Vec<f64> {
+    pub fn read_all(&self, source: crate::acquire::Source) -> Result<Vec<f64>, crate::Error> {
+        let size = self.buffer_size()?;
         let data = self
             .socket
             .send(format!("ACQ:{}:DATA?", Into::<String>::into(source)))
             .unwrap();
 
-        Self::parse(&data)
+        Self::parse(&data, Some(size as usize))
     }
 
-    fn parse(data: &str) -> Vec<f64> {
-        data.trim_matches(|c: char| c == '{' || c == '}' || c == '!' || c.is_alphabetic())
-            .split(',')
-            .map(|s| match s.parse::<f64>() {
-                Ok(f) => f,
-                Err(_) => {
-                    log::error!("Invalid data '{s}'");
-                    0.0
-                }
-            })
-            .collect()
+    /**
+     * Parse `{v1,v2,...}`, checking the number of values if `len` is known.
+     */
+    fn parse(data: &str, len: Option<usize>) -> Result<Vec<f64>, crate::Error> {
+        let data = data.trim();
+
+        if data.starts_with("ERR!") {
+            return Err(crate::Error::Firmware(data.to_owned()));
+        }
+
+        let values = parse_list(data)?;
+
+        match len {
+            Some(expected) if expected != values.len() => Err(crate::Error::Length {
+                expected,
+                actual: values.len(),
+            }),
+            _ => Ok(values),
+        }
     }
 
     /**
@@ -168,8 +191,11 @@ impl Data {
      * Trigger delay by default is set to zero (in samples or in seconds). If
      * trigger delay is set to zero it will read m samples starting from trigger.
      */
-    #[must_use]
-    pub fn read_oldest(&self, source: crate::acquire::Source, len: u32) -> Vec<f64> {
+    pub fn read_oldest(
+        &self,
+        source: crate::acquire::Source,
+        len: u32,
+    ) -> Result<Vec<f64>, crate::Error> {
         let data = self
             .socket
             .send(format!(
@@ -178,7 +204,7 @@ impl Data {
             ))
             .unwrap();
 
-        Self::parse(&data)
+        Self::parse(&data, Some(len as usize))
     }
 
     /**
@@ -187,8 +213,11 @@ impl Data {
      * Trigger delay by default is set to zero (in samples or in seconds). If
      * trigger delay is set to zero it will read m samples before trigger.
      */
-    #[must_use]
-    pub fn read_latest(&self, source: crate::acquire::Source, len: u32) -> Vec<f64> {
+    pub fn read_latest(
+        &self,
+        source: crate::acquire::Source,
+        len: u32,
+    ) -> Result<Vec<f64>, crate::Error> {
         let data = self
             .socket
             .send(format!(
@@ -197,7 +226,160 @@ impl Data {
             ))
             .unwrap();
 
-        Self::parse(&data)
+        Self::parse(&data, Some(len as usize))
+    }
+
+    /**
+     * Read several sources from the same acquisition.
+     *
+     * `range` is in samples from the oldest one, `..` reads the full buffer.
+     * Queries are pipelined on one connection: a first batch reads the
+     * acquisition state, a second one the samples of every source.
+     */
+    pub fn read_channels<R>(
+        &self,
+        sources: &[crate::acquire::Source],
+        range: R,
+    ) -> Result<crate::waveform::Waveform, crate::Error>
+    where
+        R: std::ops::RangeBounds<u32>,
+    {
+        let state = self.read_state(sources)?;
+
+        let start = match range.start_bound() {
+            std::ops::Bound::Included(start) => *start,
+            std::ops::Bound::Excluded(start) => start + 1,
+            std::ops::Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            std::ops::Bound::Included(end) => end + 1,
+            std::ops::Bound::Excluded(end) => *end,
+            std::ops::Bound::Unbounded => state.size,
+        };
+
+        if end > state.size {
+            return Err(crate::Error::OutOfRange {
+                value: end.into(),
+                min: 0.,
+                max: state.size.into(),
+            });
+        }
+        if start > end {
+            return Err(crate::Error::OutOfRange {
+                value: start.into(),
+                min: 0.,
+                max: end.into(),
+            });
+        }
+
+        self.read_window(sources, &state, start, end - start)
+    }
+
+    /**
+     * Read `pre_trigger` samples before the trigger and `post_trigger` from
+     * it, of several sources from the same acquisition.
+     *
+     * Queries are pipelined like `Data::read_channels`.
+     */
+    pub fn read_record(
+        &self,
+        sources: &[crate::acquire::Source],
+        pre_trigger: u32,
+        post_trigger: u32,
+    ) -> Result<crate::waveform::Waveform, crate::Error> {
+        let state = self.read_state(sources)?;
+        let trigger = state.trigger();
+
+        if pre_trigger > trigger {
+            return Err(crate::Error::OutOfRange {
+                value: pre_trigger.into(),
+                min: 0.,
+                max: trigger.into(),
+            });
+        }
+        if post_trigger > state.size - trigger {
+            return Err(crate::Error::OutOfRange {
+                value: post_trigger.into(),
+                min: 0.,
+                max: (state.size - trigger).into(),
+            });
+        }
+
+        self.read_window(
+            sources,
+            &state,
+            trigger - pre_trigger,
+            pre_trigger + post_trigger,
+        )
+    }
+
+    fn read_state(&self, sources: &[crate::acquire::Source]) -> Result<State, crate::Error> {
+        let mut queries = vec![
+            "ACQ:BUF:SIZE?".to_owned(),
+            "ACQ:WPOS?".to_owned(),
+            "ACQ:TPOS?".to_owned(),
+            "ACQ:DEC?".to_owned(),
+            "ACQ:DATA:UNITS?".to_owned(),
+        ];
+        for source in sources {
+            queries.push(format!("ACQ:{}:GAIN?", Into::<String>::into(*source)));
+        }
+
+        let state = self.socket.send_all(&queries);
+        let timestamp = std::time::SystemTime::now();
+
+        Ok(State {
+            size: state[0].parse()?,
+            write_pointer: state[1].parse()?,
+            trigger_position: state[2].parse()?,
+            decimation: state[3].parse()?,
+            units: state[4].parse()?,
+            gains: state[5..]
+                .iter()
+                .map(|gain| gain.parse())
+                .collect::<Result<_, _>>()?,
+            timestamp,
+        })
+    }
+
+    /**
+     * `start` is in samples from the oldest one.
+     */
+    fn read_window(
+        &self,
+        sources: &[crate::acquire::Source],
+        state: &State,
+        start: u32,
+        len: u32,
+    ) -> Result<crate::waveform::Waveform, crate::Error> {
+        let queries = sources
+            .iter()
+            .map(|source| {
+                format!(
+                    "ACQ:{}:DATA:STA:N? {},{len}",
+                    Into::<String>::into(*source),
+                    (state.write_pointer + start) % state.size,
+                )
+            })
+            .collect::<Vec<_>>();
+        let data = self.socket.send_all(&queries);
+
+        let mut channels = Vec::new();
+        for ((source, gain), data) in sources.iter().zip(&state.gains).zip(data) {
+            channels.push(crate::waveform::Channel {
+                source: *source,
+                gain: *gain,
+                samples: Self::parse(&data, Some(len as usize))?,
+            });
+        }
+
+        Ok(crate::waveform::Waveform {
+            channels,
+            decimation: state.decimation,
+            trigger: state.trigger() as isize - start as isize,
+            units: state.units,
+            timestamp: state.timestamp,
+        })
     }
 
     /**
@@ -208,6 +390,52 @@ impl Data {
     }
 }
 
+/**
+ * Parse a list of values, with or without braces.
+ */
+pub(crate) fn parse_list<T>(data: &str) -> Result<Vec<T>, crate::Error>
+where
+    T: std::str::FromStr,
+{
+    let data = data.trim();
+    let data = match data.strip_prefix('{') {
+        Some(data) => data.strip_suffix('}').ok_or(crate::Error::Truncated)?,
+        None => data,
+    };
+
+    data.split(',')
+        .enumerate()
+        .map(|(position, token)| {
+            token.trim().parse().map_err(|_| crate::Error::Parse {
+                token: token.to_owned(),
+                position,
+            })
+        })
+        .collect()
+}
+
+/**
+ * Acquisition state read before the samples.
+ */
+struct State {
+    size: u32,
+    write_pointer: u32,
+    trigger_position: u32,
+    decimation: crate::acquire::Decimation,
+    units: Unit,
+    gains: Vec<crate::acquire::Gain>,
+    timestamp: std::time::SystemTime,
+}
+
+impl State {
+    /**
+     * Trigger position in samples from the oldest one.
+     */
+    fn trigger(&self) -> u32 {
+        (self.trigger_position + self.size - self.write_pointer) % self.size
+    }
+}
+
 #[cfg(test)]
 mod test {
     #[test]
@@ -256,7 +484,22 @@ mod test {
     fn test_read_slice() {
         let (_, rp) = crate::test::create_client();
 
-        let vec = rp.data.read_slice(crate::acquire::Source::IN1, 10, 12);
+        let vec = rp
+            .data
+            .read_slice(crate::acquire::Source::IN1, 10, 12)
+            .unwrap();
+
+        #[cfg(feature = "mock")]
+        assert_eq!(vec, vec![123.0, 231.0, -231.0]);
+
+        #[cfg(not(feature = "mock"))]
+        assert_eq!(vec.len(), 3);
+
+        // Wraps around the end of the buffer
+        let vec = rp
+            .data
+            .read_slice(crate::acquire::Source::IN1, 16_383, 1)
+            .unwrap();
 
         #[cfg(feature = "mock")]
         assert_eq!(vec, vec![123.0, 231.0, -231.0]);
@@ -269,7 +512,7 @@ mod test {
     fn test_read() {
         let (_, rp) = crate::test::create_client();
 
-        let vec = rp.data.read(crate::acquire::Source::IN1, 10, 3);
+        let vec = rp.data.read(crate::acquire::Source::IN1, 10, 3).unwrap();
 
         #[cfg(feature = "mock")]
         assert_eq!(vec, vec![1.2, 3.2, -1.2]);
@@ -282,39 +525,173 @@ mod test {
     fn test_read_all() {
         let (_, rp) = crate::test::create_client();
 
-        let vec = rp.data.read_all(crate::acquire::Source::IN1);
+        let vec = rp.data.read_all(crate::acquire::Source::IN1).unwrap();
+        assert_eq!(vec.len(), 16_384);
 
         #[cfg(feature = "mock")]
-        assert_eq!(vec, vec![1.2, 3.2, -1.2]);
-
-        #[cfg(not(feature = "mock"))]
-        assert!(vec.len() > 0);
+        {
+            assert_eq!(vec[0], 0.5);
+
+            assert_eq!(
+                rp.data.read_all(crate::acquire::Source::IN2),
+                Err(crate::Error::Length {
+                    expected: 16_384,
+                    actual: 3,
+                })
+            );
+        }
     }
 
     #[test]
     fn test_read_oldest() {
         let (_, rp) = crate::test::create_client();
 
-        let vec = rp.data.read_oldest(crate::acquire::Source::IN1, 2);
+        let vec = rp.data.read_oldest(crate::acquire::Source::IN1, 2).unwrap();
 
         #[cfg(feature = "mock")]
         assert_eq!(vec, vec![3.2, -1.2]);
 
         #[cfg(not(feature = "mock"))]
-        assert!(vec.len() > 0);
+        assert!(!vec.is_empty());
     }
 
     #[test]
     fn test_read_latest() {
         let (_, rp) = crate::test::create_client();
 
-        let vec = rp.data.read_latest(crate::acquire::Source::IN1, 2);
+        let vec = rp.data.read_latest(crate::acquire::Source::IN1, 2).unwrap();
 
         #[cfg(feature = "mock")]
         assert_eq!(vec, vec![1.2, 3.2]);
 
         #[cfg(not(feature = "mock"))]
-        assert!(vec.len() > 0);
+        assert!(!vec.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "mock")]
+    fn test_read_channels() {
+        let (_, rp) = crate::test::create_client();
+
+        let waveform = rp
+            .data
+            .read_channels(
+                &[crate::acquire::Source::IN1, crate::acquire::Source::IN2],
+                ..,
+            )
+            .unwrap();
+
+        assert_eq!(waveform.trigger, 15_872);
+        assert_eq!(waveform.decimation, crate::acquire::Decimation::DEC_1);
+        assert_eq!(waveform.units, crate::data::Unit::RAW);
+        assert_eq!(waveform.channels.len(), 2);
+        assert_eq!(waveform.channels[1].source, crate::acquire::Source::IN2);
+        assert_eq!(waveform.channels[1].gain, crate::acquire::Gain::LV);
+        assert_eq!(waveform.channels[1].samples.len(), 16_384);
+        assert_eq!(waveform.channels[1].samples[0], -0.5);
+
+        let waveform = rp
+            .data
+            .read_channels(&[crate::acquire::Source::IN1], 15_360..15_363)
+            .unwrap();
+        assert_eq!(waveform.trigger, 512);
+        assert_eq!(waveform.channels[0].samples, vec![1.2, 3.2, -1.2]);
+
+        assert_eq!(
+            rp.data
+                .read_channels(&[crate::acquire::Source::IN1], 0..16_385),
+            Err(crate::Error::OutOfRange {
+                value: 16_385.,
+                min: 0.,
+                max: 16_384.,
+            })
+        );
+        assert_eq!(
+            rp.data.read_channels(
+                &[crate::acquire::Source::IN1],
+                (std::ops::Bound::Included(10), std::ops::Bound::Excluded(5))
+            ),
+            Err(crate::Error::OutOfRange {
+                value: 10.,
+                min: 0.,
+                max: 5.,
+            })
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "mock")]
+    fn test_read_record() {
+        let (_, rp) = crate::test::create_client();
+
+        let waveform = rp
+            .data
+            .read_record(&[crate::acquire::Source::IN1], 2, 1)
+            .unwrap();
+        assert_eq!(waveform.trigger, 2);
+        assert_eq!(waveform.channels[0].samples, vec![1.2, 3.2, -1.2]);
+
+        assert_eq!(
+            rp.data.read_record(&[crate::acquire::Source::IN1], 2, 513),
+            Err(crate::Error::OutOfRange {
+                value: 513.,
+                min: 0.,
+                max: 512.,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_list() {
+        assert_eq!(
+            crate::data::parse_list::<f32>("{1, 0.5,-0.2}\r\n"),
+            Ok(vec![1.0, 0.5, -0.2])
+        );
+        assert_eq!(
+            crate::data::parse_list::<f32>("1,ERR!"),
+            Err(crate::Error::Parse {
+                token: "ERR!".to_owned(),
+                position: 1,
+            })
+        );
+        assert_eq!(
+            crate::data::parse_list::<f32>("{1,0.5,0."),
+            Err(crate::Error::Truncated)
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            crate::data::Data::parse("{1.2, 3.2,-1.2}", Some(3)),
+            Ok(vec![1.2, 3.2, -1.2])
+        );
+        assert_eq!(
+            crate::data::Data::parse("1.2,3.2", None),
+            Ok(vec![1.2, 3.2])
+        );
+        assert_eq!(
+            crate::data::Data::parse("ERR!", Some(3)),
+            Err(crate::Error::Firmware("ERR!".to_owned()))
+        );
+        assert_eq!(
+            crate::data::Data::parse("{1.2,ERR!,-1.2}", None),
+            Err(crate::Error::Parse {
+                token: "ERR!".to_owned(),
+                position: 1,
+            })
+        );
+        assert_eq!(
+            crate::data::Data::parse("{1.2,3.2,-1", Some(3)),
+            Err(crate::Error::Truncated)
+        );
+        assert_eq!(
+            crate::data::Data::parse("{1.2,3.2}", Some(3)),
+            Err(crate::Error::Length {
+                expected: 3,
+                actual: 2,
+            })
+        );
     }
 
     #[test]
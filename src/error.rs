@@ -0,0 +1,186 @@
+#[derive(Clone, Debug, PartialEq)]
+pub enum Error {
+    /**
+     * The channel isn’t part of the data.
+     */
+    Channel(crate::acquire::Source),
+    /**
+     * The trace is in another unit than the expected one.
+     */
+    Dimension {
+        expected: crate::math::Unit,
+        actual: crate::math::Unit,
+    },
+    /**
+     * The board returned its error sentinel.
+     */
+    Firmware(String),
+    /**
+     * Invalid file content.
+     */
+    Format(String),
+    Io(String),
+    /**
+     * The number of values differs from the expected one.
+     */
+    Length {
+        expected: usize,
+        actual: usize,
+    },
+    /**
+     * A measurement can’t be done on the captured data.
+     */
+    Measure(crate::measure::Invalid),
+    /**
+     * A value differs from the expected one.
+     */
+    Mismatch {
+        position: usize,
+        expected: f64,
+        actual: f64,
+    },
+    /**
+     * The value is outside of the range accepted by the board.
+     */
+    OutOfRange {
+        value: f64,
+        min: f64,
+        max: f64,
+    },
+    /**
+     * Invalid value in a list.
+     */
+    Parse {
+        token: String,
+        position: usize,
+    },
+    /**
+     * The spectrum bins are spaced by another frequency than the expected
+     * one, in hertz.
+     */
+    Resolution {
+        expected: f64,
+        actual: f64,
+    },
+    /**
+     * The board answered something we don’t understand.
+     */
+    Response(String),
+    /**
+     * The data has been sampled at another rate than the expected one.
+     */
+    SamplingRate {
+        expected: crate::acquire::SamplingRate,
+        actual: crate::acquire::SamplingRate,
+    },
+    /**
+     * The board didn’t answer in time.
+     */
+    Timeout,
+    /**
+     * The response has been cut before its end.
+     */
+    Truncated,
+    /**
+     * The data is in other units than the expected ones.
+     */
+    Units {
+        expected: crate::data::Unit,
+        actual: crate::data::Unit,
+    },
+    /**
+     * The feature isn’t available on this board.
+     */
+    Unsupported(crate::general::Board),
+    /**
+     * The spectrum has been computed with another window than the expected
+     * one.
+     */
+    Window {
+        expected: crate::spectrum::Window,
+        actual: crate::spectrum::Window,
+    },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Channel(source) => write!(f, "No {source} channel"),
+            Error::Dimension { expected, actual } => {
+                write!(f, "Expected a trace in {expected}, got {actual}")
+            }
+            Error::Firmware(response) => write!(f, "Board error '{response}'"),
+            Error::Format(error) => write!(f, "Invalid format: {error}"),
+            Error::Io(error) => write!(f, "I/O error: {error}"),
+            Error::Length { expected, actual } => {
+                write!(f, "Expected {expected} values, got {actual}")
+            }
+            Error::Measure(invalid) => write!(f, "Invalid measurement: {invalid}"),
+            Error::Mismatch {
+                position,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Expected {expected} at position {position}, got {actual}"
+            ),
+            Error::OutOfRange { value, min, max } => {
+                write!(f, "Value {value} is out of range [{min}, {max}]")
+            }
+            Error::Parse { token, position } => {
+                write!(f, "Invalid value '{token}' at position {position}")
+            }
+            Error::Resolution { expected, actual } => {
+                write!(f, "Expected a {expected} Hz resolution, got {actual} Hz")
+            }
+            Error::Response(response) => write!(f, "Invalid response '{response}'"),
+            Error::SamplingRate { expected, actual } => {
+                write!(f, "Expected a {expected} sampling rate, got {actual}")
+            }
+            Error::Timeout => write!(f, "Timeout"),
+            Error::Truncated => write!(f, "Truncated response"),
+            Error::Units { expected, actual } => write!(
+                f,
+                "Expected {} units, got {}",
+                String::from(*expected),
+                String::from(*actual)
+            ),
+            Error::Unsupported(board) => write!(f, "Unsupported on {board}"),
+            Error::Window { expected, actual } => {
+                write!(f, "Expected a {expected} window, got {actual}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl std::convert::From<String> for Error {
+    fn from(error: String) -> Self {
+        Error::Response(error)
+    }
+}
+
+impl std::convert::From<std::num::ParseFloatError> for Error {
+    fn from(error: std::num::ParseFloatError) -> Self {
+        Error::Response(error.to_string())
+    }
+}
+
+impl std::convert::From<std::num::ParseIntError> for Error {
+    fn from(error: std::num::ParseIntError) -> Self {
+        Error::Response(error.to_string())
+    }
+}
+
+impl std::convert::From<crate::measure::Invalid> for Error {
+    fn from(invalid: crate::measure::Invalid) -> Self {
+        Error::Measure(invalid)
+    }
+}
+
+impl std::convert::From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error.to_string())
+    }
+}
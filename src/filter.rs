@@ -0,0 +1,566 @@
+pub mod iir;
+
+use crate::acquire::SamplingRate;
+use crate::spectrum::Window;
+use crate::spectrum::fft::Complex;
+
+/**
+ * Frequency response, cutoffs in Hz.
+ */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Response {
+    LOW_PASS(f64),
+    HIGH_PASS(f64),
+    BAND_PASS(f64, f64),
+    /**
+     * Band-stop between both frequencies.
+     */
+    NOTCH(f64, f64),
+}
+
+impl Response {
+    /**
+     * Cutoffs must be between 0 and the Nyquist frequency, in increasing
+     * order.
+     */
+    fn check(self, sampling: f64) -> Result<(), crate::Error> {
+        let nyquist = sampling / 2.;
+        let check = |frequency: f64, min: f64| {
+            if frequency > min && frequency < nyquist {
+                Ok(())
+            } else {
+                Err(crate::Error::OutOfRange {
+                    value: frequency,
+                    min,
+                    max: nyquist,
+                })
+            }
+        };
+
+        match self {
+            Response::LOW_PASS(cutoff) | Response::HIGH_PASS(cutoff) => check(cutoff, 0.),
+            Response::BAND_PASS(low, high) | Response::NOTCH(low, high) => {
+                check(low, 0.)?;
+                check(high, low)
+            }
+        }
+    }
+}
+
+pub trait Filter {
+    /**
+     * Filter the next sample.
+     */
+    fn filter(&mut self, sample: f64) -> f64;
+
+    /**
+     * Clear the state, as if the input had always been 0.
+     */
+    fn reset(&mut self);
+
+    /**
+     * Set the state as if the input had always been `value`.
+     */
+    fn settle(&mut self, value: f64);
+
+    /**
+     * Number of past samples used, sets the padding of `filtfilt`.
+     */
+    fn order(&self) -> usize;
+
+    /**
+     * Sampling rate the filter has been designed for, `None` if it works at
+     * any rate.
+     */
+    fn sampling_rate(&self) -> Option<SamplingRate> {
+        None
+    }
+
+    /**
+     * Check the filter can process data sampled at `sampling_rate`.
+     */
+    fn check_sampling_rate(&self, sampling_rate: SamplingRate) -> Result<(), crate::Error> {
+        match self.sampling_rate() {
+            Some(expected) if expected != sampling_rate => Err(crate::Error::SamplingRate {
+                expected,
+                actual: sampling_rate,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    fn process(&mut self, samples: &[f64]) -> Vec<f64> {
+        samples.iter().map(|x| self.filter(*x)).collect()
+    }
+}
+
+/**
+ * Zero-phase filtering: filter forward then backward, squaring the
+ * magnitude response.
+ *
+ * Both ends are extended by odd reflection on `3 × order` samples and the
+ * filter settled on the first value to reduce transients. The filter is
+ * reset afterwards.
+ */
+pub fn filtfilt<F: Filter + ?Sized>(filter: &mut F, samples: &[f64]) -> Vec<f64> {
+    let n = samples.len();
+
+    if n == 0 {
+        return Vec::new();
+    }
+
+    let pad = (3 * filter.order()).min(n - 1);
+    let (first, last) = (samples[0], samples[n - 1]);
+
+    let extended = (1..=pad)
+        .rev()
+        .map(|i| 2. * first - samples[i])
+        .chain(samples.iter().copied())
+        .chain((1..=pad).map(|i| 2. * last - samples[n - 1 - i]))
+        .collect::<Vec<_>>();
+
+    filter.settle(extended[0]);
+    let mut output = filter.process(&extended);
+    output.reverse();
+
+    filter.settle(output[0]);
+    let mut output = filter.process(&output);
+    output.reverse();
+
+    filter.reset();
+    output[pad..pad + n].to_vec()
+}
+
+/**
+ * Finite impulse response filter.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fir {
+    taps: Vec<f64>,
+    history: Vec<f64>,
+    position: usize,
+    sampling_rate: Option<SamplingRate>,
+}
+
+impl Fir {
+    #[must_use]
+    pub fn new(taps: Vec<f64>) -> Self {
+        Self {
+            history: vec![0.; taps.len()],
+            taps,
+            position: 0,
+            sampling_rate: None,
+        }
+    }
+
+    /**
+     * Windowed sinc design, `taps` is rounded up to an odd number so the
+     * delay is a whole number of samples.
+     */
+    pub fn design(
+        response: Response,
+        taps: usize,
+        window: Window,
+        sampling_rate: SamplingRate,
+    ) -> Result<Self, crate::Error> {
+        let sampling = sampling_rate.frequency();
+        response.check(sampling)?;
+
+        let taps = taps | 1;
+        let mut coefficients = window.coefficients(taps - 1);
+        coefficients.push(coefficients.first().copied().unwrap_or(1.));
+
+        let low_pass = |cutoff: f64| {
+            let cutoff = cutoff / sampling;
+            let center = (taps / 2) as f64;
+
+            let h = coefficients
+                .iter()
+                .enumerate()
+                .map(|(n, w)| {
+                    let x = n as f64 - center;
+                    let sinc = if x == 0. {
+                        2. * cutoff
+                    } else {
+                        (std::f64::consts::TAU * cutoff * x).sin() / (std::f64::consts::PI * x)
+                    };
+
+                    sinc * w
+                })
+                .collect::<Vec<_>>();
+
+            let sum = h.iter().sum::<f64>();
+            h.into_iter().map(|h| h / sum).collect::<Vec<_>>()
+        };
+        let impulse = |n: usize| if n == taps / 2 { 1. } else { 0. };
+
+        let h = match response {
+            Response::LOW_PASS(cutoff) => low_pass(cutoff),
+            Response::HIGH_PASS(cutoff) => low_pass(cutoff)
+                .iter()
+                .enumerate()
+                .map(|(n, h)| impulse(n) - h)
+                .collect(),
+            Response::BAND_PASS(low, high) => low_pass(high)
+                .iter()
+                .zip(low_pass(low))
+                .map(|(high, low)| high - low)
+                .collect(),
+            Response::NOTCH(low, high) => low_pass(high)
+                .iter()
+                .zip(low_pass(low))
+                .enumerate()
+                .map(|(n, (high, low))| impulse(n) - (high - low))
+                .collect(),
+        };
+
+        Ok(Self {
+            sampling_rate: Some(sampling_rate),
+            ..Self::new(h)
+        })
+    }
+
+    #[must_use]
+    pub fn taps(&self) -> &[f64] {
+        &self.taps
+    }
+
+    /**
+     * Complex gain at `frequency` Hz, `sampling_rate` must be the design one
+     * if any.
+     */
+    pub fn response(
+        &self,
+        frequency: f64,
+        sampling_rate: SamplingRate,
+    ) -> Result<Complex, crate::Error> {
+        self.check_sampling_rate(sampling_rate)?;
+
+        let omega = std::f64::consts::TAU * frequency / sampling_rate.frequency();
+
+        Ok(self
+            .taps
+            .iter()
+            .enumerate()
+            .fold(Complex::default(), |acc, (n, h)| {
+                acc + Complex::from_polar(*h, -omega * n as f64)
+            }))
+    }
+}
+
+impl Filter for Fir {
+    fn filter(&mut self, sample: f64) -> f64 {
+        let len = self.taps.len();
+
+        if len == 0 {
+            return 0.;
+        }
+
+        self.history[self.position] = sample;
+
+        let output = self
+            .taps
+            .iter()
+            .enumerate()
+            .map(|(k, h)| h * self.history[(self.position + len - k) % len])
+            .sum();
+
+        self.position = (self.position + 1) % len;
+
+        output
+    }
+
+    fn reset(&mut self) {
+        self.settle(0.);
+    }
+
+    fn settle(&mut self, value: f64) {
+        self.history.fill(value);
+    }
+
+    fn order(&self) -> usize {
+        self.taps.len().saturating_sub(1)
+    }
+
+    fn sampling_rate(&self) -> Option<SamplingRate> {
+        self.sampling_rate
+    }
+}
+
+/**
+ * Mean of the last `len` samples.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct MovingAverage {
+    fir: Fir,
+}
+
+impl MovingAverage {
+    #[must_use]
+    pub fn new(len: usize) -> Self {
+        let len = len.max(1);
+
+        Self {
+            fir: Fir::new(vec![1. / len as f64; len]),
+        }
+    }
+}
+
+impl Filter for MovingAverage {
+    fn filter(&mut self, sample: f64) -> f64 {
+        self.fir.filter(sample)
+    }
+
+    fn reset(&mut self) {
+        self.fir.reset();
+    }
+
+    fn settle(&mut self, value: f64) {
+        self.fir.settle(value);
+    }
+
+    fn order(&self) -> usize {
+        self.fir.order()
+    }
+}
+
+/**
+ * Median of the last `len` samples, removes impulsive noise.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Median {
+    window: std::collections::VecDeque<f64>,
+    len: usize,
+}
+
+impl Median {
+    #[must_use]
+    pub fn new(len: usize) -> Self {
+        let len = len.max(1);
+
+        Self {
+            window: std::collections::VecDeque::from(vec![0.; len]),
+            len,
+        }
+    }
+}
+
+impl Filter for Median {
+    fn filter(&mut self, sample: f64) -> f64 {
+        self.window.pop_front();
+        self.window.push_back(sample);
+
+        let mut sorted = self.window.iter().copied().collect::<Vec<_>>();
+        sorted.sort_by(f64::total_cmp);
+
+        let middle = sorted.len() / 2;
+        if sorted.len() % 2 == 0 {
+            (sorted[middle - 1] + sorted[middle]) / 2.
+        } else {
+            sorted[middle]
+        }
+    }
+
+    fn reset(&mut self) {
+        self.settle(0.);
+    }
+
+    fn settle(&mut self, value: f64) {
+        self.window = std::collections::VecDeque::from(vec![value; self.len]);
+    }
+
+    fn order(&self) -> usize {
+        self.len - 1
+    }
+}
+
+impl crate::waveform::Waveform {
+    /**
+     * Apply `filter` to every channel, forward and backward if `zero_phase`.
+     *
+     * The filter is reset before each channel. It must have been designed for
+     * the sampling rate of the capture, if any.
+     */
+    pub fn filter<F: Filter + ?Sized>(
+        &self,
+        filter: &mut F,
+        zero_phase: bool,
+    ) -> Result<Self, crate::Error> {
+        filter.check_sampling_rate(self.sampling_rate())?;
+
+        let channels = self
+            .channels
+            .iter()
+            .map(|channel| {
+                let samples = if zero_phase {
+                    filtfilt(filter, &channel.samples)
+                } else {
+                    filter.reset();
+                    filter.process(&channel.samples)
+                };
+
+                crate::waveform::Channel {
+                    samples,
+                    ..channel.clone()
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            channels,
+            ..self.clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::filter::Filter;
+
+    const RATE: crate::acquire::SamplingRate = crate::acquire::SamplingRate::RATE_15_2kHz;
+
+    #[test]
+    fn test_fir() {
+        let fir = crate::filter::Fir::design(
+            crate::filter::Response::LOW_PASS(1e3),
+            100,
+            crate::spectrum::Window::HAMMING,
+            RATE,
+        )
+        .unwrap();
+
+        assert_eq!(fir.taps().len(), 101);
+        assert!((fir.taps().iter().sum::<f64>() - 1.).abs() < 1e-12);
+        assert!((fir.response(1e3, RATE).unwrap().norm() - 0.5).abs() < 0.05);
+        assert!(fir.response(5e3, RATE).unwrap().norm() < 1e-2);
+
+        let fir = crate::filter::Fir::design(
+            crate::filter::Response::HIGH_PASS(1e3),
+            101,
+            crate::spectrum::Window::HAMMING,
+            RATE,
+        )
+        .unwrap();
+        assert!(fir.taps().iter().sum::<f64>().abs() < 1e-12);
+        assert!((fir.response(5e3, RATE).unwrap().norm() - 1.).abs() < 1e-2);
+
+        let fir = crate::filter::Fir::design(
+            crate::filter::Response::BAND_PASS(1e3, 3e3),
+            201,
+            crate::spectrum::Window::BLACKMAN_HARRIS,
+            RATE,
+        )
+        .unwrap();
+        assert!((fir.response(2e3, RATE).unwrap().norm() - 1.).abs() < 1e-2);
+        assert!(fir.response(6e3, RATE).unwrap().norm() < 1e-2);
+
+        let fir = crate::filter::Fir::design(
+            crate::filter::Response::NOTCH(1e3, 3e3),
+            201,
+            crate::spectrum::Window::BLACKMAN_HARRIS,
+            RATE,
+        )
+        .unwrap();
+        assert!(fir.response(2e3, RATE).unwrap().norm() < 1e-2);
+        assert!((fir.response(6e3, RATE).unwrap().norm() - 1.).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(
+            crate::filter::Fir::design(
+                crate::filter::Response::LOW_PASS(10e3),
+                11,
+                crate::spectrum::Window::HANN,
+                RATE,
+            ),
+            Err(crate::Error::OutOfRange {
+                value: 10e3,
+                min: 0.,
+                max: RATE.frequency() / 2.,
+            })
+        );
+        assert_eq!(
+            crate::filter::Fir::design(
+                crate::filter::Response::BAND_PASS(2e3, 1e3),
+                11,
+                crate::spectrum::Window::HANN,
+                RATE,
+            ),
+            Err(crate::Error::OutOfRange {
+                value: 1e3,
+                min: 2e3,
+                max: RATE.frequency() / 2.,
+            })
+        );
+    }
+
+    #[test]
+    fn test_moving_average() {
+        let mut filter = crate::filter::MovingAverage::new(3);
+
+        assert_eq!(filter.process(&[3., 6., 9., 3.]), vec![1., 3., 6., 6.]);
+    }
+
+    #[test]
+    fn test_median() {
+        let mut filter = crate::filter::Median::new(3);
+        filter.settle(1.);
+
+        assert_eq!(
+            filter.process(&[1., 9., 1., 2., 2.]),
+            vec![1., 1., 1., 2., 2.]
+        );
+    }
+
+    #[test]
+    fn test_filtfilt() {
+        let samples = crate::test::sine(1., 100., 0., RATE.frequency(), 2_000);
+        let mut fir = crate::filter::Fir::design(
+            crate::filter::Response::LOW_PASS(1e3),
+            31,
+            crate::spectrum::Window::HANN,
+            RATE,
+        )
+        .unwrap();
+
+        // No delay, even at the ends
+        let output = crate::filter::filtfilt(&mut fir, &samples);
+        assert_eq!(output.len(), samples.len());
+        assert!(
+            output
+                .iter()
+                .zip(&samples)
+                .all(|(y, x)| (y - x).abs() < 1e-2)
+        );
+
+        // The causal filter delays by 15 samples
+        let output = fir.process(&samples);
+        assert!((output[1_015] - samples[1_000]).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_waveform() {
+        let waveform = crate::test::waveform(vec![0., 3., 0., 0.]);
+
+        let mut filter = crate::filter::MovingAverage::new(3);
+        let filtered = waveform.filter(&mut filter, false).unwrap();
+        assert_eq!(filtered.channels[0].samples, vec![0., 1., 1., 1.]);
+        assert_eq!(filtered.decimation, waveform.decimation);
+
+        let mut fir = crate::filter::Fir::design(
+            crate::filter::Response::LOW_PASS(1e3),
+            31,
+            crate::spectrum::Window::HANN,
+            RATE,
+        )
+        .unwrap();
+        assert_eq!(
+            waveform.filter(&mut fir, true),
+            Err(crate::Error::SamplingRate {
+                expected: RATE,
+                actual: crate::acquire::SamplingRate::RATE_125MHz,
+            })
+        );
+    }
+}
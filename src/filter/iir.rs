@@ -0,0 +1,559 @@
+use super::{Filter, Response};
+use crate::acquire::SamplingRate;
+use crate::spectrum::fft::Complex;
+
+/**
+ * Second order section, in transposed direct form II.
+ */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Biquad {
+    /**
+     * Numerator coefficients.
+     */
+    pub b: [f64; 3],
+    /**
+     * Denominator coefficients, `a0` being 1.
+     */
+    pub a: [f64; 2],
+    state: [f64; 2],
+}
+
+impl Biquad {
+    #[must_use]
+    pub fn new(b: [f64; 3], a: [f64; 2]) -> Self {
+        Self {
+            b,
+            a,
+            state: [0.; 2],
+        }
+    }
+
+    /**
+     * Complex gain at `frequency` Hz.
+     */
+    #[must_use]
+    pub fn response(&self, frequency: f64, sampling_rate: SamplingRate) -> Complex {
+        let omega = std::f64::consts::TAU * frequency / sampling_rate.frequency();
+        let polynomial = |c: [f64; 3]| {
+            c.iter()
+                .enumerate()
+                .fold(Complex::default(), |acc, (n, c)| {
+                    acc + Complex::from_polar(*c, -omega * n as f64)
+                })
+        };
+
+        polynomial(self.b) / polynomial([1., self.a[0], self.a[1]])
+    }
+
+    fn dc_gain(&self) -> f64 {
+        self.b.iter().sum::<f64>() / (1. + self.a[0] + self.a[1])
+    }
+}
+
+impl Filter for Biquad {
+    fn filter(&mut self, sample: f64) -> f64 {
+        let output = self.b[0] * sample + self.state[0];
+
+        self.state[0] = self.b[1] * sample - self.a[0] * output + self.state[1];
+        self.state[1] = self.b[2] * sample - self.a[1] * output;
+
+        output
+    }
+
+    fn reset(&mut self) {
+        self.state = [0.; 2];
+    }
+
+    fn settle(&mut self, value: f64) {
+        let output = self.dc_gain() * value;
+
+        self.state[1] = self.b[2] * value - self.a[1] * output;
+        self.state[0] = self.b[1] * value - self.a[0] * output + self.state[1];
+    }
+
+    fn order(&self) -> usize {
+        2
+    }
+}
+
+/**
+ * Cascade of second order sections.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Iir {
+    pub sections: Vec<Biquad>,
+    /**
+     * Rate the sections have been designed for, `None` for any rate.
+     */
+    pub sampling_rate: Option<SamplingRate>,
+}
+
+/**
+ * Zeros, poles and gain of a transfer function.
+ */
+#[derive(Clone, Debug)]
+struct Zpk {
+    zeros: Vec<Complex>,
+    poles: Vec<Complex>,
+    gain: f64,
+}
+
+impl Iir {
+    /**
+     * Maximally flat pass band, -3 dB at the cutoffs.
+     *
+     * Band-pass and notch responses have twice `order` poles.
+     */
+    pub fn butterworth(
+        response: Response,
+        order: usize,
+        sampling_rate: SamplingRate,
+    ) -> Result<Self, crate::Error> {
+        check_order(order)?;
+
+        let poles = (0..order)
+            .map(|k| {
+                let angle = std::f64::consts::PI * (2 * k + order + 1) as f64 / (2 * order) as f64;
+                Complex::from_polar(1., angle)
+            })
+            .collect();
+
+        let prototype = Zpk {
+            zeros: Vec::new(),
+            poles,
+            gain: 1.,
+        };
+
+        Self::design(prototype, response, sampling_rate)
+    }
+
+    /**
+     * Chebyshev type I, `ripple` dB in the pass band which ends at the
+     * cutoffs, steeper than Butterworth.
+     *
+     * Band-pass and notch responses have twice `order` poles.
+     */
+    pub fn chebyshev(
+        response: Response,
+        order: usize,
+        ripple: f64,
+        sampling_rate: SamplingRate,
+    ) -> Result<Self, crate::Error> {
+        check_order(order)?;
+
+        if ripple.is_nan() || ripple <= 0. {
+            return Err(crate::Error::OutOfRange {
+                value: ripple,
+                min: 0.,
+                max: f64::INFINITY,
+            });
+        }
+
+        let epsilon = (10f64.powf(ripple / 10.) - 1.).sqrt();
+        let mu = (1. / epsilon).asinh() / order as f64;
+
+        let poles = (0..order)
+            .map(|k| {
+                let theta = std::f64::consts::PI * (2 * k + 1) as f64 / (2 * order) as f64;
+                Complex::new(-mu.sinh() * theta.sin(), mu.cosh() * theta.cos())
+            })
+            .collect::<Vec<_>>();
+
+        let mut gain = product(poles.iter().map(|p| Complex::default() - *p)).re;
+        if order.is_multiple_of(2) {
+            gain /= (1. + epsilon * epsilon).sqrt();
+        }
+
+        let prototype = Zpk {
+            zeros: Vec::new(),
+            poles,
+            gain,
+        };
+
+        Self::design(prototype, response, sampling_rate)
+    }
+
+    /**
+     * Transform the analog low-pass `prototype`, with a 1 rad/s cutoff, to
+     * `response` then to digital with the bilinear transform.
+     */
+    fn design(
+        prototype: Zpk,
+        response: Response,
+        sampling_rate: SamplingRate,
+    ) -> Result<Self, crate::Error> {
+        let sampling = sampling_rate.frequency();
+        response.check(sampling)?;
+
+        // Pre-warp the cutoffs so they are exact after the bilinear transform
+        let warp =
+            |frequency: f64| 2. * sampling * (std::f64::consts::PI * frequency / sampling).tan();
+
+        let analog = match response {
+            Response::LOW_PASS(cutoff) => low_pass(prototype, warp(cutoff)),
+            Response::HIGH_PASS(cutoff) => high_pass(prototype, warp(cutoff)),
+            Response::BAND_PASS(low, high) => {
+                let (low, high) = (warp(low), warp(high));
+                band_pass(prototype, (low * high).sqrt(), high - low)
+            }
+            Response::NOTCH(low, high) => {
+                let (low, high) = (warp(low), warp(high));
+                notch(prototype, (low * high).sqrt(), high - low)
+            }
+        };
+
+        Ok(Self {
+            sections: sections(bilinear(analog, sampling)),
+            sampling_rate: Some(sampling_rate),
+        })
+    }
+
+    /**
+     * Complex gain at `frequency` Hz, `sampling_rate` must be the design one
+     * if any.
+     */
+    pub fn response(
+        &self,
+        frequency: f64,
+        sampling_rate: SamplingRate,
+    ) -> Result<Complex, crate::Error> {
+        self.check_sampling_rate(sampling_rate)?;
+
+        Ok(self
+            .sections
+            .iter()
+            .fold(Complex::from(1.), |acc, section| {
+                acc * section.response(frequency, sampling_rate)
+            }))
+    }
+}
+
+impl Filter for Iir {
+    fn filter(&mut self, sample: f64) -> f64 {
+        self.sections
+            .iter_mut()
+            .fold(sample, |input, section| section.filter(input))
+    }
+
+    fn reset(&mut self) {
+        self.sections.iter_mut().for_each(Filter::reset);
+    }
+
+    fn settle(&mut self, mut value: f64) {
+        for section in &mut self.sections {
+            section.settle(value);
+            value *= section.dc_gain();
+        }
+    }
+
+    fn order(&self) -> usize {
+        2 * self.sections.len()
+    }
+
+    fn sampling_rate(&self) -> Option<SamplingRate> {
+        self.sampling_rate
+    }
+}
+
+fn check_order(order: usize) -> Result<(), crate::Error> {
+    if order == 0 {
+        return Err(crate::Error::OutOfRange {
+            value: 0.,
+            min: 1.,
+            max: f64::INFINITY,
+        });
+    }
+
+    Ok(())
+}
+
+fn product(values: impl Iterator<Item = Complex>) -> Complex {
+    values.fold(Complex::from(1.), |acc, value| acc * value)
+}
+
+/**
+ * Gain ratio `Π(x − zeros) / Π(x − poles)`, real for conjugate roots.
+ */
+fn ratio(zpk: &Zpk, x: Complex) -> f64 {
+    let zeros = product(zpk.zeros.iter().map(|z| x - *z));
+    let poles = product(zpk.poles.iter().map(|p| x - *p));
+
+    (zeros / poles).re
+}
+
+fn degree(zpk: &Zpk) -> usize {
+    zpk.poles.len() - zpk.zeros.len()
+}
+
+fn low_pass(zpk: Zpk, omega: f64) -> Zpk {
+    Zpk {
+        gain: zpk.gain * omega.powi(degree(&zpk) as i32),
+        zeros: zpk.zeros.iter().map(|z| z.scale(omega)).collect(),
+        poles: zpk.poles.iter().map(|p| p.scale(omega)).collect(),
+    }
+}
+
+fn high_pass(zpk: Zpk, omega: f64) -> Zpk {
+    let invert = |roots: &[Complex]| {
+        roots
+            .iter()
+            .map(|r| Complex::from(omega) / *r)
+            .collect::<Vec<_>>()
+    };
+
+    let mut zeros = invert(&zpk.zeros);
+    zeros.resize(zpk.poles.len(), Complex::default());
+
+    Zpk {
+        gain: zpk.gain * ratio(&zpk, Complex::default()),
+        zeros,
+        poles: invert(&zpk.poles),
+    }
+}
+
+/**
+ * Each root `r` gives `r ± √(r² − ω₀²)`.
+ */
+fn split(roots: &[Complex], omega: f64) -> Vec<Complex> {
+    roots
+        .iter()
+        .flat_map(|r| {
+            let delta = (*r * *r - Complex::from(omega * omega)).sqrt();
+            [*r + delta, *r - delta]
+        })
+        .collect()
+}
+
+fn band_pass(zpk: Zpk, omega: f64, bandwidth: f64) -> Zpk {
+    let scale = |roots: &[Complex]| {
+        roots
+            .iter()
+            .map(|r| r.scale(bandwidth / 2.))
+            .collect::<Vec<_>>()
+    };
+
+    let mut zeros = split(&scale(&zpk.zeros), omega);
+    zeros.resize(zeros.len() + degree(&zpk), Complex::default());
+
+    Zpk {
+        gain: zpk.gain * bandwidth.powi(degree(&zpk) as i32),
+        zeros,
+        poles: split(&scale(&zpk.poles), omega),
+    }
+}
+
+fn notch(zpk: Zpk, omega: f64, bandwidth: f64) -> Zpk {
+    let invert = |roots: &[Complex]| {
+        roots
+            .iter()
+            .map(|r| Complex::from(bandwidth / 2.) / *r)
+            .collect::<Vec<_>>()
+    };
+
+    let mut zeros = split(&invert(&zpk.zeros), omega);
+    for _ in 0..degree(&zpk) {
+        zeros.push(Complex::new(0., omega));
+        zeros.push(Complex::new(0., -omega));
+    }
+
+    Zpk {
+        gain: zpk.gain * ratio(&zpk, Complex::default()),
+        zeros,
+        poles: split(&invert(&zpk.poles), omega),
+    }
+}
+
+fn bilinear(zpk: Zpk, sampling: f64) -> Zpk {
+    let fs2 = Complex::from(2. * sampling);
+    let map = |roots: &[Complex]| {
+        roots
+            .iter()
+            .map(|r| (fs2 + *r) / (fs2 - *r))
+            .collect::<Vec<_>>()
+    };
+
+    let mut zeros = map(&zpk.zeros);
+    zeros.resize(zpk.poles.len(), Complex::from(-1.));
+
+    Zpk {
+        gain: zpk.gain * ratio(&zpk, fs2),
+        zeros,
+        poles: map(&zpk.poles),
+    }
+}
+
+/**
+ * Group conjugate pairs, then real roots two by two, the odd one last.
+ */
+fn groups(roots: &[Complex]) -> Vec<Vec<Complex>> {
+    let tolerance = |r: &Complex| 1e-9 * r.norm().max(1.);
+
+    let mut groups = roots
+        .iter()
+        .filter(|r| r.im > tolerance(r))
+        .map(|r| vec![*r, r.conj()])
+        .collect::<Vec<_>>();
+
+    let mut reals = roots
+        .iter()
+        .filter(|r| r.im.abs() <= tolerance(r))
+        .map(|r| Complex::from(r.re))
+        .collect::<Vec<_>>();
+    reals.sort_by(|a, b| a.re.total_cmp(&b.re));
+
+    groups.extend(reals.chunks(2).map(<[Complex]>::to_vec));
+    groups
+}
+
+/**
+ * Monic polynomial coefficients from up to two roots.
+ */
+fn polynomial(roots: &[Complex]) -> [f64; 3] {
+    match roots {
+        [r] => [1., -r.re, 0.],
+        [r1, r2] => [1., -(*r1 + *r2).re, (*r1 * *r2).re],
+        _ => [1., 0., 0.],
+    }
+}
+
+fn sections(zpk: Zpk) -> Vec<Biquad> {
+    groups(&zpk.zeros)
+        .iter()
+        .zip(groups(&zpk.poles))
+        .enumerate()
+        .map(|(i, (zeros, poles))| {
+            let gain = if i == 0 { zpk.gain } else { 1. };
+            let b = polynomial(zeros).map(|b| b * gain);
+            let a = polynomial(&poles);
+
+            Biquad::new(b, [a[1], a[2]])
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::filter::Filter;
+    use crate::filter::Response;
+
+    const RATE: crate::acquire::SamplingRate = crate::acquire::SamplingRate::RATE_103_8kHz;
+
+    fn gain(iir: &crate::filter::iir::Iir, frequency: f64) -> f64 {
+        20. * iir.response(frequency, RATE).unwrap().norm().log10()
+    }
+
+    #[test]
+    fn test_butterworth() {
+        let iir = crate::filter::iir::Iir::butterworth(Response::LOW_PASS(1e3), 4, RATE).unwrap();
+
+        assert_eq!(iir.sections.len(), 2);
+        assert!(gain(&iir, 0.).abs() < 1e-9);
+        assert!((gain(&iir, 1e3) + 3.01).abs() < 1e-2);
+        // 24 dB per octave
+        assert!(gain(&iir, 10e3) < -75.);
+
+        let iir = crate::filter::iir::Iir::butterworth(Response::HIGH_PASS(1e3), 3, RATE).unwrap();
+        assert_eq!(iir.sections.len(), 2);
+        assert!(gain(&iir, 20e3).abs() < 1e-2);
+        assert!((gain(&iir, 1e3) + 3.01).abs() < 1e-2);
+        assert!(gain(&iir, 100.) < -55.);
+    }
+
+    #[test]
+    fn test_band() {
+        let iir =
+            crate::filter::iir::Iir::butterworth(Response::BAND_PASS(1e3, 4e3), 2, RATE).unwrap();
+
+        assert!(gain(&iir, 2e3).abs() < 1e-2);
+        assert!((gain(&iir, 1e3) + 3.01).abs() < 1e-2);
+        assert!((gain(&iir, 4e3) + 3.01).abs() < 1e-2);
+        assert!(gain(&iir, 100.) < -35.);
+
+        let iir = crate::filter::iir::Iir::butterworth(Response::NOTCH(45., 55.), 1, RATE).unwrap();
+        assert!(gain(&iir, (45f64 * 55.).sqrt()) < -60.);
+        assert!((gain(&iir, 45.) + 3.01).abs() < 1e-2);
+        assert!(gain(&iir, 1e3).abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_chebyshev() {
+        let iir = crate::filter::iir::Iir::chebyshev(Response::LOW_PASS(1e3), 4, 1., RATE).unwrap();
+
+        // Even order starts at the bottom of the ripple
+        assert!((gain(&iir, 0.) + 1.).abs() < 1e-6);
+        assert!((gain(&iir, 1e3) + 1.).abs() < 1e-6);
+        assert!(gain(&iir, 600.) < 1e-6);
+
+        let butterworth =
+            crate::filter::iir::Iir::butterworth(Response::LOW_PASS(1e3), 4, RATE).unwrap();
+        assert!(gain(&iir, 3e3) < gain(&butterworth, 3e3) - 10.);
+
+        let iir =
+            crate::filter::iir::Iir::chebyshev(Response::HIGH_PASS(1e3), 3, 0.5, RATE).unwrap();
+        assert!(gain(&iir, 20e3).abs() < 0.5);
+        assert!((gain(&iir, 1e3) + 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_process() {
+        // 100 samples per period
+        let frequency = RATE.frequency() / 100.;
+        let mut iir =
+            crate::filter::iir::Iir::butterworth(Response::LOW_PASS(frequency), 2, RATE).unwrap();
+        let samples = crate::test::sine(1., frequency, 0., RATE.frequency(), 10_000);
+
+        // Steady state amplitude after the transient
+        let output = iir.process(&samples);
+        let peak = output[5_000..].iter().fold(0f64, |max, y| max.max(y.abs()));
+        assert!((peak - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-3);
+
+        // Settled on a constant, the output doesn’t move
+        iir.settle(2.);
+        assert!((iir.filter(2.) - 2.).abs() < 1e-12);
+
+        // Zero phase, the gain is squared
+        let output = crate::filter::filtfilt(&mut iir, &samples);
+        assert!((output[5_025] - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_order() {
+        assert_eq!(
+            crate::filter::iir::Iir::butterworth(Response::LOW_PASS(1e3), 0, RATE),
+            Err(crate::Error::OutOfRange {
+                value: 0.,
+                min: 1.,
+                max: f64::INFINITY,
+            })
+        );
+        assert!(crate::filter::iir::Iir::chebyshev(Response::LOW_PASS(1e3), 0, 1., RATE).is_err());
+    }
+
+    #[test]
+    fn test_ripple() {
+        assert_eq!(
+            crate::filter::iir::Iir::chebyshev(Response::LOW_PASS(1e3), 4, 0., RATE),
+            Err(crate::Error::OutOfRange {
+                value: 0.,
+                min: 0.,
+                max: f64::INFINITY,
+            })
+        );
+        assert!(
+            crate::filter::iir::Iir::chebyshev(Response::LOW_PASS(1e3), 4, f64::NAN, RATE).is_err()
+        );
+    }
+
+    #[test]
+    fn test_response_sampling_rate() {
+        let iir = crate::filter::iir::Iir::butterworth(Response::LOW_PASS(1e3), 2, RATE).unwrap();
+
+        assert_eq!(
+            iir.response(1e3, crate::acquire::SamplingRate::RATE_125MHz),
+            Err(crate::Error::SamplingRate {
+                expected: RATE,
+                actual: crate::acquire::SamplingRate::RATE_125MHz,
+            })
+        );
+    }
+}
@@ -1,5 +1,64 @@
 use crate::socket::Socket;
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Board {
+    STEMLAB_125_10,
+    STEMLAB_125_14,
+    SIGNALLAB_250_12,
+}
+
+impl std::str::FromStr for Board {
+    type Err = String;
+
+    /**
+     * Parse the board name returned by `SYST:BRD:Name?`, for example
+     * `STEMlab 125-14 v1.1`.
+     */
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.contains("125-10") {
+            Ok(Board::STEMLAB_125_10)
+        } else if s.contains("125-14") {
+            Ok(Board::STEMLAB_125_14)
+        } else if s.contains("250-12") {
+            Ok(Board::SIGNALLAB_250_12)
+        } else {
+            Err(format!("Unknow board '{s}'"))
+        }
+    }
+}
+
+impl std::fmt::Display for Board {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let display = match self {
+            Board::STEMLAB_125_10 => "STEMlab 125-10",
+            Board::STEMLAB_125_14 => "STEMlab 125-14",
+            Board::SIGNALLAB_250_12 => "SIGNALlab 250-12",
+        };
+
+        write!(f, "{display}")
+    }
+}
+
+/**
+ * Board model of `socket`, read with `SYST:BRD:Name?` on first use unless set
+ * by `General::set_board`.
+ */
+pub(crate) fn board(socket: &Socket) -> Result<Board, crate::Error> {
+    let mut board = socket
+        .board
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+    if let Some(board) = *board {
+        return Ok(board);
+    }
+
+    let detected = socket.query("SYST:BRD:Name?")?;
+    *board = Some(detected);
+
+    Ok(detected)
+}
+
 #[derive(Clone, Debug)]
 pub struct General {
     socket: Socket,
@@ -50,6 +109,24 @@ impl General {
     pub fn enable_digital_loop(&self) {
         self.socket.send("RP:DIG:LOop");
     }
+
+    /**
+     * Get the board model, detected once and shared by all the modules.
+     */
+    pub fn board(&self) -> Result<Board, crate::Error> {
+        board(&self.socket)
+    }
+
+    /**
+     * Select the board model of all the modules, instead of detecting it.
+     */
+    pub fn set_board(&self, board: Board) {
+        *self
+            .socket
+            .board
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(board);
+    }
 }
 
 #[cfg(test)]
@@ -91,4 +168,21 @@ mod test {
     fn test_enable_digital_loop() {
         general_assert!(enable_digital_loop, "RP:DIG:LOop\r\n");
     }
+
+    #[test]
+    fn test_board() {
+        let (_, rp) = crate::test::create_client();
+
+        assert_eq!(
+            rp.general.board(),
+            Ok(crate::general::Board::STEMLAB_125_14)
+        );
+
+        rp.general
+            .set_board(crate::general::Board::SIGNALLAB_250_12);
+        assert_eq!(
+            rp.general.board(),
+            Ok(crate::general::Board::SIGNALLAB_250_12)
+        );
+    }
 }
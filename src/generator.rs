@@ -1,6 +1,12 @@
+pub mod import;
+pub mod settings;
+pub mod waveform;
+
+use crate::general::Board;
 use crate::socket::Socket;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum TriggerSource {
     EXT_PE,
     EXT_NE,
@@ -36,6 +42,7 @@ impl std::str::FromStr for TriggerSource {
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Form {
     SINE,
     SQUARE,
@@ -137,6 +144,86 @@ impl std::fmt::Display for Source {
     }
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Load {
+    INF,
+    L50,
+}
+
+impl std::convert::From<Load> for String {
+    fn from(load: Load) -> Self {
+        let s = match load {
+            Load::INF => "INF",
+            Load::L50 => "L50",
+        };
+
+        String::from(s)
+    }
+}
+
+impl std::str::FromStr for Load {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "INF" => Ok(Load::INF),
+            "L50" => Ok(Load::L50),
+            load => Err(format!("Unknow load '{load}'")),
+        }
+    }
+}
+
+impl std::fmt::Display for Load {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let display = match self {
+            Load::INF => "Hi-Z",
+            Load::L50 => "50 Ω",
+        };
+
+        write!(f, "{display}")
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Gain {
+    X1,
+    X5,
+}
+
+impl std::convert::From<Gain> for String {
+    fn from(gain: Gain) -> Self {
+        let s = match gain {
+            Gain::X1 => "X1",
+            Gain::X5 => "X5",
+        };
+
+        String::from(s)
+    }
+}
+
+impl std::str::FromStr for Gain {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "X1" => Ok(Gain::X1),
+            "X5" => Ok(Gain::X5),
+            gain => Err(format!("Unknow gain '{gain}'")),
+        }
+    }
+}
+
+impl std::fmt::Display for Gain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let display = match self {
+            Gain::X1 => "x1",
+            Gain::X5 => "x5",
+        };
+
+        write!(f, "{display}")
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Generator {
     socket: Socket,
@@ -149,6 +236,13 @@ impl crate::Module for Generator {
 }
 
 impl Generator {
+    fn check_signallab(&self) -> Result<(), crate::Error> {
+        match crate::general::board(&self.socket)? {
+            Board::SIGNALLAB_250_12 => Ok(()),
+            board => Err(crate::Error::Unsupported(board)),
+        }
+    }
+
     /**
      * Enable fast analog outputs.
      */
@@ -195,12 +289,10 @@ impl Generator {
     /**
      * Get frequency of fast analog outputs.
      */
-    pub fn frequency(&self, source: Source) -> Result<u32, <f32 as std::str::FromStr>::Err> {
+    pub fn frequency(&self, source: Source) -> Result<u32, crate::Error> {
         let value: f32 = self
             .socket
-            .send(format!("{}:FREQ:FIX?", Into::<String>::into(source)))
-            .unwrap()
-            .parse()?;
+            .query(format!("{}:FREQ:FIX?", Into::<String>::into(source)))?;
 
         Ok(value as u32)
     }
@@ -218,19 +310,26 @@ impl Generator {
         ));
     }
 
-    pub fn form(&self, source: Source) -> Result<Form, String> {
+    pub fn form(&self, source: Source) -> Result<Form, crate::Error> {
         self.socket
-            .send(format!("{}:FUNC?", Into::<String>::into(source)))
-            .unwrap()
-            .parse()
+            .query(format!("{}:FUNC?", Into::<String>::into(source)))
     }
 
     /**
      * Set amplitude voltage of fast analog outputs.
      *
-     * Amplitude + offset value must be less than maximum output range ± 1V
+     * Amplitude + offset value must be within the output range, see
+     * `Generator::output_range`. The current offset is read back first, and
+     * on SIGNALlab 250-12 the gain and load too: each is an extra query.
      */
-    pub fn set_amplitude(&self, source: Source, amplitude: f32) {
+    pub fn set_amplitude(&self, source: Source, amplitude: f32) -> Result<(), crate::Error> {
+        self.check_output(source, amplitude, self.offset(source)?)?;
+        self.send_amplitude(source, amplitude);
+
+        Ok(())
+    }
+
+    fn send_amplitude(&self, source: Source, amplitude: f32) {
         self.socket.send(format!(
             "{}:VOLT {}",
             Into::<String>::into(source),
@@ -241,19 +340,26 @@ impl Generator {
     /**
      * Get amplitude voltage of fast analog outputs.
      */
-    pub fn amplitude(&self, source: Source) -> Result<f32, <f32 as std::str::FromStr>::Err> {
+    pub fn amplitude(&self, source: Source) -> Result<f32, crate::Error> {
         self.socket
-            .send(format!("{}:VOLT?", Into::<String>::into(source)))
-            .unwrap()
-            .parse()
+            .query(format!("{}:VOLT?", Into::<String>::into(source)))
     }
 
     /**
      * Set offset voltage of fast analog outputs.
      *
-     * Amplitude + offset value must be less than maximum output range ± 1V
+     * Amplitude + offset value must be within the output range, see
+     * `Generator::output_range`. The current amplitude is read back first,
+     * and on SIGNALlab 250-12 the gain and load too: each is an extra query.
      */
-    pub fn set_offset(&self, source: Source, offset: f32) {
+    pub fn set_offset(&self, source: Source, offset: f32) -> Result<(), crate::Error> {
+        self.check_output(source, self.amplitude(source)?, offset)?;
+        self.send_offset(source, offset);
+
+        Ok(())
+    }
+
+    fn send_offset(&self, source: Source, offset: f32) {
         self.socket.send(format!(
             "{}:VOLT:OFFS {}",
             Into::<String>::into(source),
@@ -261,14 +367,137 @@ impl Generator {
         ));
     }
 
+    /**
+     * Configure a sine of `frequency` Hz and `amplitude` volts without
+     * offset.
+     *
+     * The offset is cleared before the amplitude is set, so the output stays
+     * within its range whatever the previous offset was.
+     */
+    pub fn set_sine(
+        &self,
+        source: Source,
+        frequency: u32,
+        amplitude: f32,
+    ) -> Result<(), crate::Error> {
+        self.check_output(source, amplitude, 0.)?;
+
+        self.set_form(source, Form::SINE);
+        self.set_frequency(source, frequency);
+        self.send_offset(source, 0.);
+        self.send_amplitude(source, amplitude);
+
+        Ok(())
+    }
+
     /**
      * Get offset voltage of fast analog outputs.
      */
-    pub fn offset(&self, source: Source) -> Result<f32, <f32 as std::str::FromStr>::Err> {
+    pub fn offset(&self, source: Source) -> Result<f32, crate::Error> {
         self.socket
-            .send(format!("{}:VOLT:OFFS?", Into::<String>::into(source)))
-            .unwrap()
-            .parse()
+            .query(format!("{}:VOLT:OFFS?", Into::<String>::into(source)))
+    }
+
+    /**
+     * Set output load, only available on SIGNALlab 250-12.
+     *
+     * This affects the output amplitude.
+     */
+    pub fn set_load(&self, source: Source, load: Load) -> Result<(), crate::Error> {
+        self.check_signallab()?;
+
+        self.socket.send(format!(
+            "{}:LOAD {}",
+            Into::<String>::into(source),
+            Into::<String>::into(load)
+        ));
+
+        Ok(())
+    }
+
+    /**
+     * Get output load, only available on SIGNALlab 250-12.
+     */
+    pub fn load(&self, source: Source) -> Result<Load, crate::Error> {
+        self.check_signallab()?;
+
+        self.socket
+            .query(format!("{}:LOAD?", Into::<String>::into(source)))
+    }
+
+    /**
+     * Set output amplifier gain, only available on SIGNALlab 250-12.
+     */
+    pub fn set_gain(&self, source: Source, gain: Gain) -> Result<(), crate::Error> {
+        self.check_signallab()?;
+
+        self.socket.send(format!(
+            "{}:GAIN {}",
+            Into::<String>::into(source),
+            Into::<String>::into(gain)
+        ));
+
+        Ok(())
+    }
+
+    /**
+     * Get output amplifier gain, only available on SIGNALlab 250-12.
+     */
+    pub fn gain(&self, source: Source) -> Result<Gain, crate::Error> {
+        self.check_signallab()?;
+
+        self.socket
+            .query(format!("{}:GAIN?", Into::<String>::into(source)))
+    }
+
+    /**
+     * Get the maximum output voltage, in volts, for the current board, load
+     * and gain.
+     *
+     * | Board            | Gain | 50 Ω   | Hi-Z   |
+     * |------------------|------|--------|--------|
+     * | STEMlab 125-xx   |      | ± 1 V  | ± 1 V  |
+     * | SIGNALlab 250-12 | x1   | ± 1 V  | ± 2 V  |
+     * | SIGNALlab 250-12 | x5   | ± 5 V  | ± 10 V |
+     */
+    pub fn output_range(&self, source: Source) -> Result<f32, crate::Error> {
+        let range = match crate::general::board(&self.socket)? {
+            Board::STEMLAB_125_10 | Board::STEMLAB_125_14 => 1.,
+            Board::SIGNALLAB_250_12 => {
+                let range = match self.gain(source)? {
+                    Gain::X1 => 1.,
+                    Gain::X5 => 5.,
+                };
+
+                match self.load(source)? {
+                    Load::L50 => range,
+                    Load::INF => 2. * range,
+                }
+            }
+        };
+
+        Ok(range)
+    }
+
+    fn check_output(
+        &self,
+        source: Source,
+        amplitude: f32,
+        offset: f32,
+    ) -> Result<(), crate::Error> {
+        let max = self.output_range(source)?;
+
+        for value in [offset + amplitude.abs(), offset - amplitude.abs()] {
+            if value.abs() > max {
+                return Err(crate::Error::OutOfRange {
+                    value: value.into(),
+                    min: (-max).into(),
+                    max: max.into(),
+                });
+            }
+        }
+
+        Ok(())
     }
 
     /**
@@ -282,11 +511,9 @@ impl Generator {
     /**
      * Get phase of fast analog outputs.
      */
-    pub fn phase(&self, source: Source) -> Result<i32, <i32 as std::str::FromStr>::Err> {
+    pub fn phase(&self, source: Source) -> Result<i32, crate::Error> {
         self.socket
-            .send(format!("{}:PHAS?", Into::<String>::into(source)))
-            .unwrap()
-            .parse()
+            .query(format!("{}:PHAS?", Into::<String>::into(source)))
     }
 
     /**
@@ -300,15 +527,15 @@ impl Generator {
     /**
      * Get duty cycle of PWM waveform.
      */
-    pub fn duty_cycle(&self, source: Source) -> Result<f32, <f32 as std::str::FromStr>::Err> {
+    pub fn duty_cycle(&self, source: Source) -> Result<f32, crate::Error> {
         self.socket
-            .send(format!("{}:DCYC?", Into::<String>::into(source)))
-            .unwrap()
-            .parse()
+            .query(format!("{}:DCYC?", Into::<String>::into(source)))
     }
 
     /**
      * Import data for arbitrary waveform generation.
+     *
+     * See the `waveform` module to build common shapes.
      */
     pub fn set_arbitrary_waveform(&self, source: Source, data: &[f32]) {
         let mut data = data
@@ -322,20 +549,101 @@ impl Generator {
         ));
     }
 
+    /**
+     * Import the `column` of a CSV file for arbitrary waveform generation.
+     */
+    pub fn load_csv<P>(
+        &self,
+        source: Source,
+        path: P,
+        column: usize,
+        scaling: import::Scaling,
+    ) -> Result<(), crate::Error>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        let data = import::csv(file, column, scaling)?;
+
+        self.set_arbitrary_waveform(source, &data);
+
+        Ok(())
+    }
+
+    /**
+     * Import a WAV file for arbitrary waveform generation.
+     *
+     * The first channel goes to OUT1, the second one of a stereo file to OUT2.
+     */
+    pub fn load_wav<P>(&self, path: P, scaling: import::Scaling) -> Result<(), crate::Error>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        let channels = import::wav(file, scaling)?;
+
+        for (source, data) in [Source::OUT1, Source::OUT2].into_iter().zip(channels) {
+            self.set_arbitrary_waveform(source, &data);
+        }
+
+        Ok(())
+    }
+
     /**
      * Get data for arbitrary waveform generation.
+     *
+     * The trace must fill the whole buffer of `waveform::BUFFER_SIZE` points.
      */
-    #[must_use]
-    pub fn arbitrary_waveform(&self, source: Source) -> Vec<f32> {
+    pub fn arbitrary_waveform(&self, source: Source) -> Result<Vec<f32>, crate::Error> {
         let data = self
             .socket
             .send(format!("{}:TRAC:DATA:DATA?", Into::<String>::into(source)))
-            .unwrap();
+            .ok_or(crate::Error::Truncated)?;
+
+        let trace = crate::data::parse_list::<f32>(&data)?;
+
+        if trace.len() != waveform::BUFFER_SIZE {
+            return Err(crate::Error::Length {
+                expected: waveform::BUFFER_SIZE,
+                actual: trace.len(),
+            });
+        }
+
+        Ok(trace)
+    }
+
+    /**
+     * Read back the arbitrary waveform and compare it to `expected`.
+     *
+     * Fails on the first point differing of more than `tolerance`.
+     */
+    pub fn verify_arbitrary_waveform(
+        &self,
+        source: Source,
+        expected: &[f32],
+        tolerance: f32,
+    ) -> Result<(), crate::Error> {
+        let actual = self.arbitrary_waveform(source)?;
+
+        if actual.len() != expected.len() {
+            return Err(crate::Error::Length {
+                expected: expected.len(),
+                actual: actual.len(),
+            });
+        }
 
-        data.trim_matches(|c| c == '{' || c == '}')
-            .split(',')
-            .map(|x| x.parse().unwrap())
-            .collect()
+        match expected
+            .iter()
+            .zip(actual.iter())
+            .position(|(e, a)| (e - a).abs() > tolerance)
+        {
+            Some(position) => Err(crate::Error::Mismatch {
+                position,
+                expected: expected[position].into(),
+                actual: actual[position].into(),
+            }),
+            None => Ok(()),
+        }
     }
 
     /**
@@ -352,11 +660,9 @@ impl Generator {
     /**
      * Get trigger source for selected signal.
      */
-    pub fn trigger_source(&self, source: Source) -> Result<TriggerSource, String> {
+    pub fn trigger_source(&self, source: Source) -> Result<TriggerSource, crate::Error> {
         self.socket
-            .send(format!("{}:TRIG:SOUR?", Into::<String>::into(source)))
-            .unwrap()
-            .parse()
+            .query(format!("{}:TRIG:SOUR?", Into::<String>::into(source)))
     }
 
     /**
@@ -440,7 +746,8 @@ mod test {
         assert_eq!("OUTPUT1:STATE ON\r\n", rx.recv().unwrap());
 
         rp.generator
-            .set_amplitude(crate::generator::Source::OUT1, -0.5);
+            .set_amplitude(crate::generator::Source::OUT1, -0.5)
+            .unwrap();
         assert_eq!("SOUR1:VOLT -0.5\r\n", rx.recv().unwrap());
 
         assert_eq!(
@@ -453,12 +760,113 @@ mod test {
     fn test_offset() {
         let (rx, rp) = crate::test::create_client();
 
-        rp.generator.set_offset(crate::generator::Source::OUT1, 0.3);
+        rp.generator
+            .set_offset(crate::generator::Source::OUT1, 0.3)
+            .unwrap();
         assert_eq!("SOUR1:VOLT:OFFS 0.3\r\n", rx.recv().unwrap());
 
         assert_eq!(rp.generator.offset(crate::generator::Source::OUT1), Ok(0.3));
     }
 
+    #[test]
+    fn test_sine() {
+        let (rx, rp) = crate::test::create_client();
+
+        rp.generator
+            .set_sine(crate::generator::Source::OUT1, 1_000, 0.9)
+            .unwrap();
+        assert_eq!("SOUR1:FUNC SINE\r\n", rx.recv().unwrap());
+        assert_eq!("SOUR1:FREQ:FIX 1000\r\n", rx.recv().unwrap());
+        assert_eq!("SOUR1:VOLT:OFFS 0\r\n", rx.recv().unwrap());
+        assert_eq!("SOUR1:VOLT 0.9\r\n", rx.recv().unwrap());
+
+        assert!(
+            rp.generator
+                .set_sine(crate::generator::Source::OUT1, 1_000, 1.5)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_output_range() {
+        let (rx, rp) = crate::test::create_client();
+
+        assert_eq!(
+            rp.general.board(),
+            Ok(crate::general::Board::STEMLAB_125_14)
+        );
+
+        assert_eq!(
+            rp.generator
+                .set_amplitude(crate::generator::Source::OUT1, 0.8),
+            Err(crate::Error::OutOfRange {
+                value: 1.1f32.into(),
+                min: -1.,
+                max: 1.,
+            })
+        );
+
+        rp.general
+            .set_board(crate::general::Board::SIGNALLAB_250_12);
+        assert_eq!(
+            rp.generator.output_range(crate::generator::Source::OUT1),
+            Ok(5.)
+        );
+
+        rp.generator
+            .set_amplitude(crate::generator::Source::OUT1, 0.8)
+            .unwrap();
+        assert_eq!("SOUR1:VOLT 0.8\r\n", rx.recv().unwrap());
+    }
+
+    #[test]
+    fn test_load() {
+        let (rx, rp) = crate::test::create_client();
+
+        assert_eq!(
+            rp.generator
+                .set_load(crate::generator::Source::OUT1, crate::generator::Load::L50),
+            Err(crate::Error::Unsupported(
+                crate::general::Board::STEMLAB_125_14
+            ))
+        );
+        assert_eq!(
+            rp.generator.load(crate::generator::Source::OUT1),
+            Err(crate::Error::Unsupported(
+                crate::general::Board::STEMLAB_125_14
+            ))
+        );
+
+        rp.general
+            .set_board(crate::general::Board::SIGNALLAB_250_12);
+        rp.generator
+            .set_load(crate::generator::Source::OUT1, crate::generator::Load::L50)
+            .unwrap();
+        assert_eq!("SOUR1:LOAD L50\r\n", rx.recv().unwrap());
+
+        assert_eq!(
+            rp.generator.load(crate::generator::Source::OUT1),
+            Ok(crate::generator::Load::L50)
+        );
+    }
+
+    #[test]
+    fn test_gain() {
+        let (rx, rp) = crate::test::create_client();
+
+        rp.general
+            .set_board(crate::general::Board::SIGNALLAB_250_12);
+        rp.generator
+            .set_gain(crate::generator::Source::OUT1, crate::generator::Gain::X5)
+            .unwrap();
+        assert_eq!("SOUR1:GAIN X5\r\n", rx.recv().unwrap());
+
+        assert_eq!(
+            rp.generator.gain(crate::generator::Source::OUT1),
+            Ok(crate::generator::Gain::X5)
+        );
+    }
+
     #[test]
     fn test_phase() {
         let (rx, rp) = crate::test::create_client();
@@ -491,20 +899,97 @@ mod test {
             .set_arbitrary_waveform(crate::generator::Source::OUT1, &[1.0, 0.5, 0.2]);
         assert_eq!("SOUR1:TRAC:DATA:DATA 1,0.5,0.2\r\n", rx.recv().unwrap());
 
+        let trace = rp
+            .generator
+            .arbitrary_waveform(crate::generator::Source::OUT1)
+            .unwrap();
+        assert_eq!(trace.len(), crate::generator::waveform::BUFFER_SIZE);
+
         #[cfg(feature = "mock")]
+        {
+            assert_eq!(trace[..4], [1.0, 0.5, 0.2, 0.0]);
+
+            assert_eq!(
+                rp.generator
+                    .arbitrary_waveform(crate::generator::Source::OUT2),
+                Err(crate::Error::Length {
+                    expected: 16_384,
+                    actual: 3,
+                })
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "mock")]
+    fn test_verify_arbitrary_waveform() {
+        let (_, rp) = crate::test::create_client();
+
+        let mut expected = vec![0.0; crate::generator::waveform::BUFFER_SIZE];
+        expected[..3].copy_from_slice(&[1.0, 0.5, 0.2001]);
         assert_eq!(
-            rp.generator
-                .arbitrary_waveform(crate::generator::Source::OUT1),
-            vec![1.0, 0.5, 0.2]
+            rp.generator.verify_arbitrary_waveform(
+                crate::generator::Source::OUT1,
+                &expected,
+                0.001
+            ),
+            Ok(())
         );
 
-        #[cfg(not(feature = "mock"))]
-        assert!(
-            rp.generator
-                .arbitrary_waveform(crate::generator::Source::OUT1)
-                .len()
-                > 0
+        expected[1] = 0.4;
+        assert_eq!(
+            rp.generator.verify_arbitrary_waveform(
+                crate::generator::Source::OUT1,
+                &expected,
+                0.001
+            ),
+            Err(crate::Error::Mismatch {
+                position: 1,
+                expected: 0.4f32.into(),
+                actual: 0.5,
+            })
         );
+
+        assert_eq!(
+            rp.generator.verify_arbitrary_waveform(
+                crate::generator::Source::OUT1,
+                &[1.0, 0.5],
+                0.001
+            ),
+            Err(crate::Error::Length {
+                expected: 2,
+                actual: 16_384,
+            })
+        );
+    }
+
+    #[test]
+    fn test_load_wav() {
+        let (rx, rp) = crate::test::create_client();
+
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!(
+            "redpitaya-scpi-test-load-{}-{nanos}.wav",
+            std::process::id()
+        ));
+        std::fs::write(
+            &path,
+            crate::generator::import::test::wav_16bits(&[&[0, 0], &[0, 0]]),
+        )
+        .unwrap();
+
+        rp.generator
+            .load_wav(&path, crate::generator::import::Scaling::CHECK)
+            .unwrap();
+        let mut received = [rx.recv().unwrap(), rx.recv().unwrap()];
+        received.sort();
+        assert!(received[0].starts_with("SOUR1:TRAC:DATA:DATA 0,0,"));
+        assert!(received[1].starts_with("SOUR2:TRAC:DATA:DATA 0,0,"));
+
+        std::fs::remove_file(path).unwrap();
     }
 
     #[test]
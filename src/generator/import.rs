@@ -0,0 +1,340 @@
+use super::waveform::{BUFFER_SIZE, normalize, resample};
+use crate::Error;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Scaling {
+    /**
+     * Scale the signal so its peak value is ± 1.
+     */
+    NORMALIZE,
+    /**
+     * Keep values as is, but fail if a value is outside ± 1.
+     */
+    CHECK,
+}
+
+/**
+ * Read the `column` (starting at 0) of a CSV file and resample it to the
+ * arbitrary waveform buffer length.
+ *
+ * Columns are separated by tabulation, semicolon or comma, the first one
+ * found on the first line is used for the whole file. With tabulation or
+ * semicolon, decimal commas are accepted. The first non-empty line may be a
+ * header.
+ */
+pub fn csv<R>(reader: R, column: usize, scaling: Scaling) -> Result<Vec<f32>, Error>
+where
+    R: std::io::BufRead,
+{
+    let lines = reader.lines().collect::<Result<Vec<_>, _>>()?;
+    let delimiter = delimiter(&lines);
+    let mut data = Vec::new();
+    let rows = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+
+    for (row, (n, line)) in rows.enumerate() {
+        let Some(field) = line.split(delimiter).nth(column) else {
+            return Err(Error::Format(format!("line {}: no column {column}", n + 1)));
+        };
+
+        let value = field.trim().trim_matches('"');
+        let value = if delimiter == ',' {
+            value.to_owned()
+        } else {
+            value.replace(',', ".")
+        };
+
+        match value.parse() {
+            Ok(value) => data.push(value),
+            // Header
+            Err(_) if row == 0 => continue,
+            Err(_) => {
+                return Err(Error::Format(format!(
+                    "line {}: invalid value '{field}'",
+                    n + 1
+                )));
+            }
+        }
+    }
+
+    if data.is_empty() {
+        return Err(Error::Format("no data".to_owned()));
+    }
+
+    scale(&resample(&data, BUFFER_SIZE), scaling)
+}
+
+fn delimiter(lines: &[String]) -> char {
+    let first = lines
+        .iter()
+        .find(|line| !line.trim().is_empty())
+        .map_or("", String::as_str);
+
+    ['\t', ';']
+        .into_iter()
+        .find(|delimiter| first.contains(*delimiter))
+        .unwrap_or(',')
+}
+
+/**
+ * Read a mono or stereo WAV file, each channel is resampled to the arbitrary
+ * waveform buffer length.
+ *
+ * Supports 8, 16, 24 and 32 bits integer PCM and 32 and 64 bits float.
+ */
+pub fn wav<R>(mut reader: R, scaling: Scaling) -> Result<Vec<Vec<f32>>, Error>
+where
+    R: std::io::Read,
+{
+    let mut buffer = Vec::new();
+    reader.read_to_end(&mut buffer)?;
+
+    if buffer.len() < 12 || &buffer[0..4] != b"RIFF" || &buffer[8..12] != b"WAVE" {
+        return Err(Error::Format("not a WAV file".to_owned()));
+    }
+
+    let mut format = None;
+    let mut samples = None;
+    let mut chunks = &buffer[12..];
+
+    while chunks.len() >= 8 {
+        let size = u32::from_le_bytes([chunks[4], chunks[5], chunks[6], chunks[7]]) as usize;
+        let body = &chunks[8..chunks.len().min(8 + size)];
+
+        match &chunks[0..4] {
+            b"fmt " => format = Some(WavFormat::parse(body)?),
+            b"data" => samples = Some(body),
+            _ => (),
+        }
+
+        // chunks are word aligned
+        chunks = &chunks[chunks.len().min(8 + size + size % 2)..];
+    }
+
+    let format = format.ok_or_else(|| Error::Format("missing fmt chunk".to_owned()))?;
+    let samples = samples.ok_or_else(|| Error::Format("missing data chunk".to_owned()))?;
+
+    let mut channels = vec![Vec::new(); format.channels];
+
+    for (n, sample) in samples.chunks_exact(format.bits / 8).enumerate() {
+        channels[n % format.channels].push(format.decode(sample));
+    }
+
+    if channels.iter().any(Vec::is_empty) {
+        return Err(Error::Format("empty data chunk".to_owned()));
+    }
+
+    channels
+        .iter()
+        .map(|channel| scale(&resample(channel, BUFFER_SIZE), scaling))
+        .collect()
+}
+
+fn scale(data: &[f32], scaling: Scaling) -> Result<Vec<f32>, Error> {
+    let mut data = data.to_vec();
+
+    match scaling {
+        Scaling::NORMALIZE => normalize(&mut data),
+        Scaling::CHECK => {
+            if let Some(value) = data.iter().find(|x| x.abs() > 1.) {
+                return Err(Error::OutOfRange {
+                    value: (*value).into(),
+                    min: -1.,
+                    max: 1.,
+                });
+            }
+        }
+    }
+
+    Ok(data)
+}
+
+struct WavFormat {
+    float: bool,
+    channels: usize,
+    bits: usize,
+}
+
+impl WavFormat {
+    const PCM: u16 = 1;
+    const FLOAT: u16 = 3;
+    const EXTENSIBLE: u16 = 0xfffe;
+
+    fn parse(chunk: &[u8]) -> Result<Self, Error> {
+        if chunk.len() < 16 {
+            return Err(Error::Format("invalid fmt chunk".to_owned()));
+        }
+
+        let mut tag = u16::from_le_bytes([chunk[0], chunk[1]]);
+        let channels = u16::from_le_bytes([chunk[2], chunk[3]]) as usize;
+        let bits = u16::from_le_bytes([chunk[14], chunk[15]]) as usize;
+
+        if tag == Self::EXTENSIBLE && chunk.len() >= 26 {
+            tag = u16::from_le_bytes([chunk[24], chunk[25]]);
+        }
+
+        let float = match (tag, bits) {
+            (Self::PCM, 8 | 16 | 24 | 32) => false,
+            (Self::FLOAT, 32 | 64) => true,
+            _ => {
+                return Err(Error::Format(format!(
+                    "unsupported format {tag} with {bits} bits"
+                )));
+            }
+        };
+
+        if !(1..=2).contains(&channels) {
+            return Err(Error::Format(format!(
+                "{channels} channels, only mono and stereo are supported"
+            )));
+        }
+
+        Ok(Self {
+            float,
+            channels,
+            bits,
+        })
+    }
+
+    fn decode(&self, sample: &[u8]) -> f32 {
+        match (self.float, self.bits) {
+            (false, 8) => (f32::from(sample[0]) - 128.) / 128.,
+            (false, 16) => f32::from(i16::from_le_bytes([sample[0], sample[1]])) / 32_768.,
+            (false, 24) => {
+                let value = i32::from_le_bytes([0, sample[0], sample[1], sample[2]]) >> 8;
+
+                value as f32 / 8_388_608.
+            }
+            (false, _) => {
+                let value = i32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]]);
+
+                value as f32 / 2_147_483_648.
+            }
+            (true, 32) => f32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]]),
+            (true, _) => f64::from_le_bytes([
+                sample[0], sample[1], sample[2], sample[3], sample[4], sample[5], sample[6],
+                sample[7],
+            ]) as f32,
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+
+    pub(crate) fn wav_16bits(channels: &[&[i16]]) -> Vec<u8> {
+        let len = channels[0].len();
+        let mut data = Vec::new();
+
+        for n in 0..len {
+            for channel in channels {
+                data.extend_from_slice(&channel[n].to_le_bytes());
+            }
+        }
+
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes());
+        wav.extend_from_slice(&(channels.len() as u16).to_le_bytes());
+        wav.extend_from_slice(&44_100u32.to_le_bytes());
+        wav.extend_from_slice(&(44_100 * 2 * channels.len() as u32).to_le_bytes());
+        wav.extend_from_slice(&(2 * channels.len() as u16).to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        wav.extend_from_slice(&data);
+
+        wav
+    }
+
+    #[test]
+    fn test_csv() {
+        let content = "time;value\n0;0\n1;0.5\n\n2;-0.5\n";
+
+        let data = csv(content.as_bytes(), 1, Scaling::NORMALIZE).unwrap();
+        assert_eq!(data.len(), BUFFER_SIZE);
+        assert_eq!(data[0], 0.);
+        assert_eq!(data[BUFFER_SIZE - 1], -1.);
+
+        let data = csv(content.as_bytes(), 1, Scaling::CHECK).unwrap();
+        assert_eq!(data[BUFFER_SIZE - 1], -0.5);
+
+        let data = csv("\n\ntime,value\n0,0.5\n".as_bytes(), 1, Scaling::CHECK).unwrap();
+        assert_eq!(data[0], 0.5);
+    }
+
+    #[test]
+    fn test_csv_delimiter() {
+        let data = csv("0;0,25\n1;-0,5\n".as_bytes(), 1, Scaling::CHECK).unwrap();
+        assert_eq!(data[0], 0.25);
+        assert_eq!(data[BUFFER_SIZE - 1], -0.5);
+
+        let data = csv("0\t0.25\n1\t-0.5\n".as_bytes(), 1, Scaling::CHECK).unwrap();
+        assert_eq!(data[0], 0.25);
+
+        // Comma separated, the semicolon is part of the field
+        assert_eq!(
+            csv("0,0.25\n1,0;5\n".as_bytes(), 1, Scaling::CHECK),
+            Err(Error::Format("line 2: invalid value '0;5'".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_csv_error() {
+        assert_eq!(
+            csv("0,2\n1,0.5\n".as_bytes(), 1, Scaling::CHECK),
+            Err(Error::OutOfRange {
+                value: 2.,
+                min: -1.,
+                max: 1.
+            })
+        );
+
+        assert_eq!(
+            csv("0,1\n1,ERR\n".as_bytes(), 1, Scaling::CHECK),
+            Err(Error::Format("line 2: invalid value 'ERR'".to_owned()))
+        );
+
+        assert!(csv("0\n".as_bytes(), 1, Scaling::CHECK).is_err());
+
+        assert_eq!(
+            csv("time,value\n".as_bytes(), 1, Scaling::CHECK),
+            Err(Error::Format("no data".to_owned()))
+        );
+        assert_eq!(
+            csv("".as_bytes(), 1, Scaling::CHECK),
+            Err(Error::Format("no data".to_owned()))
+        );
+    }
+
+    #[test]
+    fn test_wav() {
+        let content = wav_16bits(&[&[0, 16_384], &[-32_768, 0]]);
+
+        let channels = wav(content.as_slice(), Scaling::CHECK).unwrap();
+        assert_eq!(channels.len(), 2);
+        assert_eq!(channels[0][BUFFER_SIZE - 1], 0.5);
+        assert_eq!(channels[1][0], -1.);
+
+        let channels = wav(content.as_slice(), Scaling::NORMALIZE).unwrap();
+        assert_eq!(channels[0][BUFFER_SIZE - 1], 1.);
+    }
+
+    #[test]
+    fn test_wav_error() {
+        assert_eq!(
+            wav("RIFF".as_bytes(), Scaling::CHECK),
+            Err(Error::Format("not a WAV file".to_owned()))
+        );
+        assert_eq!(
+            wav(wav_16bits(&[&[]]).as_slice(), Scaling::CHECK),
+            Err(Error::Format("empty data chunk".to_owned()))
+        );
+    }
+}
@@ -0,0 +1,282 @@
+use super::{Form, Generator, Source, TriggerSource};
+use crate::Module;
+use crate::burst::{Burst, Mode};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BurstSettings {
+    pub mode: Mode,
+    pub count: u32,
+    pub repetitions: u32,
+    /**
+     * Total time of one burst in micro seconds.
+     */
+    pub period: u32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelSettings {
+    pub form: Form,
+    pub frequency: u32,
+    pub amplitude: f32,
+    pub offset: f32,
+    pub phase: i32,
+    pub duty_cycle: f32,
+    pub trigger_source: TriggerSource,
+    pub burst: BurstSettings,
+}
+
+/**
+ * Value of a `ChannelSettings` field.
+ */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Value {
+    FORM(Form),
+    TRIGGER_SOURCE(TriggerSource),
+    MODE(Mode),
+    INTEGER(i32),
+    UNSIGNED(u32),
+    FLOAT(f32),
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::FORM(form) => write!(f, "{form}"),
+            Value::TRIGGER_SOURCE(source) => write!(f, "{}", Into::<String>::into(*source)),
+            Value::MODE(mode) => write!(f, "{}", Into::<String>::into(*mode)),
+            Value::INTEGER(value) => write!(f, "{value}"),
+            Value::UNSIGNED(value) => write!(f, "{value}"),
+            Value::FLOAT(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+macro_rules! value_from {
+    ($type:ty, $variant:ident) => {
+        impl std::convert::From<$type> for Value {
+            fn from(value: $type) -> Self {
+                Value::$variant(value)
+            }
+        }
+    };
+}
+
+value_from!(Form, FORM);
+value_from!(TriggerSource, TRIGGER_SOURCE);
+value_from!(Mode, MODE);
+value_from!(i32, INTEGER);
+value_from!(u32, UNSIGNED);
+value_from!(f32, FLOAT);
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Change {
+    pub field: &'static str,
+    pub from: Value,
+    pub to: Value,
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {} → {}", self.field, self.from, self.to)
+    }
+}
+
+impl ChannelSettings {
+    /**
+     * List fields changed between `self` and `other`.
+     */
+    #[must_use]
+    pub fn diff(&self, other: &Self) -> Vec<Change> {
+        let mut changes = Vec::new();
+
+        macro_rules! diff {
+            ($name:literal, $($field:ident).+) => {
+                if self.$($field).+ != other.$($field).+ {
+                    changes.push(Change {
+                        field: $name,
+                        from: self.$($field).+.into(),
+                        to: other.$($field).+.into(),
+                    });
+                }
+            };
+        }
+
+        diff!("form", form);
+        diff!("frequency", frequency);
+        diff!("amplitude", amplitude);
+        diff!("offset", offset);
+        diff!("phase", phase);
+        diff!("duty_cycle", duty_cycle);
+        diff!("trigger_source", trigger_source);
+        diff!("burst.mode", burst.mode);
+        diff!("burst.count", burst.count);
+        diff!("burst.repetitions", burst.repetitions);
+        diff!("burst.period", burst.period);
+
+        changes
+    }
+}
+
+impl Generator {
+    /**
+     * Read all settings of a channel.
+     */
+    pub fn read_settings(&self, source: Source) -> Result<ChannelSettings, crate::Error> {
+        let burst = Burst::new(self.socket.clone());
+
+        Ok(ChannelSettings {
+            form: self.form(source)?,
+            frequency: self.frequency(source)?,
+            amplitude: self.amplitude(source)?,
+            offset: self.offset(source)?,
+            phase: self.phase(source)?,
+            duty_cycle: self.duty_cycle(source)?,
+            trigger_source: self.trigger_source(source)?,
+            burst: BurstSettings {
+                mode: burst.mode(source.into())?,
+                count: burst.count(source.into())?,
+                repetitions: burst.repetitions(source.into())?,
+                period: burst.period(source.into())?,
+            },
+        })
+    }
+
+    /**
+     * Configure a channel, only the settings differing from the current
+     * state are sent.
+     */
+    pub fn apply(&self, source: Source, settings: &ChannelSettings) -> Result<(), crate::Error> {
+        let current = self.read_settings(source)?;
+        let burst = Burst::new(self.socket.clone());
+
+        self.check_output(source, settings.amplitude, settings.offset)?;
+
+        if current.form != settings.form {
+            self.set_form(source, settings.form);
+        }
+        if current.frequency != settings.frequency {
+            self.set_frequency(source, settings.frequency);
+        }
+
+        // Shrink the envelope first so every intermediate state stays
+        // within the output range.
+        let shrink = settings.amplitude.abs() < current.amplitude.abs();
+        if shrink && current.amplitude != settings.amplitude {
+            self.send_amplitude(source, settings.amplitude);
+        }
+        if current.offset != settings.offset {
+            self.send_offset(source, settings.offset);
+        }
+        if !shrink && current.amplitude != settings.amplitude {
+            self.send_amplitude(source, settings.amplitude);
+        }
+
+        if current.phase != settings.phase {
+            self.set_phase(source, settings.phase);
+        }
+        if current.duty_cycle != settings.duty_cycle {
+            self.set_duty_cycle(source, settings.duty_cycle);
+        }
+        if current.trigger_source != settings.trigger_source {
+            self.set_trigger_source(source, settings.trigger_source);
+        }
+        if current.burst.mode != settings.burst.mode {
+            burst.set_mode(source.into(), settings.burst.mode);
+        }
+        if current.burst.count != settings.burst.count {
+            burst.set_count(source.into(), settings.burst.count);
+        }
+        if current.burst.repetitions != settings.burst.repetitions {
+            burst.set_repetitions(source.into(), settings.burst.repetitions);
+        }
+        if current.burst.period != settings.burst.period {
+            burst.set_period(source.into(), settings.burst.period);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    fn settings() -> crate::generator::settings::ChannelSettings {
+        crate::generator::settings::ChannelSettings {
+            form: crate::generator::Form::SINE,
+            frequency: 1_000,
+            amplitude: -0.5,
+            offset: 0.3,
+            phase: 180,
+            duty_cycle: 1.0,
+            trigger_source: crate::generator::TriggerSource::BURST,
+            burst: crate::generator::settings::BurstSettings {
+                mode: crate::burst::Mode::CONTINUOUS,
+                count: 1,
+                repetitions: 5,
+                period: 1_000,
+            },
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let mut other = settings();
+        other.frequency = 2_000;
+        other.burst.mode = crate::burst::Mode::BURST;
+
+        let changes = settings().diff(&other);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].to_string(), "frequency: 1000 → 2000");
+        assert_eq!(changes[1].to_string(), "burst.mode: CONTINUOUS → BURST");
+        assert_eq!(
+            changes[1].from,
+            crate::generator::settings::Value::MODE(crate::burst::Mode::CONTINUOUS)
+        );
+
+        other.amplitude = 0.25;
+        assert_eq!(
+            settings().diff(&other)[1].to_string(),
+            "amplitude: -0.5 → 0.25"
+        );
+
+        assert!(settings().diff(&settings()).is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "mock")]
+    fn test_read_settings() {
+        let (_, rp) = crate::test::create_client();
+
+        assert_eq!(
+            rp.generator.read_settings(crate::generator::Source::OUT1),
+            Ok(settings())
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "mock")]
+    fn test_apply() {
+        let (rx, rp) = crate::test::create_client();
+
+        let mut settings = settings();
+        settings.frequency = 2_000;
+        settings.amplitude = 0.5;
+        settings.burst.count = 3;
+
+        rp.generator
+            .apply(crate::generator::Source::OUT1, &settings)
+            .unwrap();
+        crate::test::assert_received(
+            &rx,
+            &["SOUR1:FREQ:FIX 2000", "SOUR1:VOLT 0.5", "SOUR1:BURS:NCYC 3"],
+        );
+
+        settings.offset = 0.6;
+        assert!(
+            rp.generator
+                .apply(crate::generator::Source::OUT1, &settings)
+                .is_err()
+        );
+    }
+}
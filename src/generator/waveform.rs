@@ -0,0 +1,328 @@
+/**
+ * Number of points of the arbitrary waveform generator buffer.
+ */
+pub const BUFFER_SIZE: usize = 16_384;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tone {
+    /**
+     * Number of periods in the trace.
+     */
+    pub cycles: u32,
+    pub amplitude: f32,
+    /**
+     * Phase in degrees.
+     */
+    pub phase: f32,
+}
+
+/**
+ * Resample `data` to `len` points with a linear interpolation.
+ *
+ * First and last points are kept.
+ */
+#[must_use]
+pub fn resample(data: &[f32], len: usize) -> Vec<f32> {
+    match (data.len(), len) {
+        (_, 0) | (0, _) => vec![0.; len],
+        (1, _) => vec![data[0]; len],
+        (n, 1) => vec![data[n / 2]],
+        (n, _) => (0..len)
+            .map(|i| {
+                let x = i as f64 * (n - 1) as f64 / (len - 1) as f64;
+                let index = (x as usize).min(n - 2);
+                let fract = (x - index as f64) as f32;
+
+                data[index] + (data[index + 1] - data[index]) * fract
+            })
+            .collect(),
+    }
+}
+
+/**
+ * Scale `data` so its peak value is ± 1.
+ *
+ * A null signal is left untouched.
+ */
+pub fn normalize(data: &mut [f32]) {
+    let peak = data.iter().fold(0f32, |acc, x| acc.max(x.abs()));
+
+    if peak > 0. {
+        data.iter_mut().for_each(|x| *x /= peak);
+    }
+}
+
+/**
+ * Linear frequency sweep from `start` to `end` periods per trace.
+ */
+#[must_use]
+pub fn chirp(start: f32, end: f32) -> Vec<f32> {
+    build(|t| {
+        let phase = start * t + (end - start) * t * t / 2.;
+
+        (std::f32::consts::TAU * phase).sin()
+    })
+}
+
+/**
+ * Gaussian pulse centered in the trace, `width` is the standard deviation as
+ * a fraction of the trace.
+ */
+pub fn gaussian_pulse(width: f32) -> Result<Vec<f32>, crate::Error> {
+    check_positive(width)?;
+
+    Ok(build(|t| {
+        let x = (t - 0.5) / width;
+
+        (-x * x / 2.).exp()
+    }))
+}
+
+/**
+ * Sinc centered in the trace, with `zeros` zero crossings on each side.
+ */
+#[must_use]
+pub fn sinc(zeros: u32) -> Vec<f32> {
+    build(|t| {
+        let x = std::f32::consts::PI * 2. * zeros as f32 * (t - 0.5);
+
+        if x == 0. { 1. } else { x.sin() / x }
+    })
+}
+
+/**
+ * Exponential decay, `time_constant` is a fraction of the trace.
+ */
+pub fn exponential_decay(time_constant: f32) -> Result<Vec<f32>, crate::Error> {
+    check_positive(time_constant)?;
+
+    Ok(build(|t| (-t / time_constant).exp()))
+}
+
+fn check_positive(value: f32) -> Result<(), crate::Error> {
+    if value > 0. && value.is_finite() {
+        Ok(())
+    } else {
+        Err(crate::Error::OutOfRange {
+            value: value.into(),
+            min: 0.,
+            max: f64::INFINITY,
+        })
+    }
+}
+
+/**
+ * Staircase from -1 to 1 in `steps` steps, at least 2.
+ */
+pub fn staircase(steps: u32) -> Result<Vec<f32>, crate::Error> {
+    if steps < 2 {
+        return Err(crate::Error::OutOfRange {
+            value: steps.into(),
+            min: 2.,
+            max: f64::INFINITY,
+        });
+    }
+
+    Ok(build(|t| {
+        let step = ((t * steps as f32) as u32).min(steps - 1);
+
+        2. * step as f32 / (steps - 1) as f32 - 1.
+    }))
+}
+
+/**
+ * Sum of sines.
+ */
+#[must_use]
+pub fn multi_tone(tones: &[Tone]) -> Vec<f32> {
+    build(|t| {
+        tones
+            .iter()
+            .map(|tone| {
+                let phase =
+                    std::f32::consts::TAU * tone.cycles as f32 * t + tone.phase.to_radians();
+
+                tone.amplitude * phase.sin()
+            })
+            .sum()
+    })
+}
+
+/**
+ * Sum of harmonics, `harmonics[n]` is the `(amplitude, phase)` of the
+ * harmonic of rank n + 1, phase in degrees.
+ */
+#[must_use]
+pub fn harmonics(harmonics: &[(f32, f32)]) -> Vec<f32> {
+    let tones = harmonics
+        .iter()
+        .enumerate()
+        .map(|(n, &(amplitude, phase))| Tone {
+            cycles: n as u32 + 1,
+            amplitude,
+            phase,
+        })
+        .collect::<Vec<_>>();
+
+    multi_tone(&tones)
+}
+
+/**
+ * Uniform white noise, the same `seed` always gives the same trace.
+ */
+#[must_use]
+pub fn white_noise(seed: u64) -> Vec<f32> {
+    let mut rng = Rng::new(seed);
+
+    build(|_| rng.next())
+}
+
+/**
+ * Pink (1/f) noise, the same `seed` always gives the same trace.
+ */
+#[must_use]
+pub fn pink_noise(seed: u64) -> Vec<f32> {
+    let mut rng = Rng::new(seed);
+    let mut b = [0f32; 7];
+
+    // Paul Kellet’s refined method
+    build(|_| {
+        let white = rng.next();
+
+        b[0] = 0.99886 * b[0] + white * 0.055_517_9;
+        b[1] = 0.99332 * b[1] + white * 0.075_075_9;
+        b[2] = 0.96900 * b[2] + white * 0.153_852;
+        b[3] = 0.86650 * b[3] + white * 0.310_485_6;
+        b[4] = 0.55000 * b[4] + white * 0.532_952_2;
+        b[5] = -0.7616 * b[5] - white * 0.016_898;
+        let pink = b.iter().sum::<f32>() + white * 0.5362;
+        b[6] = white * 0.115_926;
+
+        pink
+    })
+}
+
+fn build<F>(mut f: F) -> Vec<f32>
+where
+    F: FnMut(f32) -> f32,
+{
+    let mut data = (0..BUFFER_SIZE)
+        .map(|i| f(i as f32 / BUFFER_SIZE as f32))
+        .collect::<Vec<_>>();
+
+    normalize(&mut data);
+
+    data
+}
+
+/**
+ * SplitMix64, good enough for test signals and without dependency.
+ */
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /**
+     * Returns a value in [-1, 1).
+     */
+    fn next(&mut self) -> f32 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+
+        (z >> 40) as f32 / (1u64 << 23) as f32 - 1.
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_normalized(data: &[f32]) {
+        assert_eq!(data.len(), BUFFER_SIZE);
+
+        let peak = data.iter().fold(0f32, |acc, x| acc.max(x.abs()));
+        assert!((peak - 1.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_resample() {
+        assert_eq!(resample(&[0., 1.], 5), vec![0., 0.25, 0.5, 0.75, 1.]);
+        assert_eq!(resample(&[0., 2., 4., 6., 8.], 3), vec![0., 4., 8.]);
+        assert_eq!(resample(&[3.], 2), vec![3., 3.]);
+    }
+
+    #[test]
+    fn test_normalize() {
+        let mut data = [0.5, -2., 1.];
+        normalize(&mut data);
+
+        assert_eq!(data, [0.25, -1., 0.5]);
+    }
+
+    #[test]
+    fn test_shapes() {
+        assert_normalized(&chirp(1., 10.));
+        assert_normalized(&gaussian_pulse(0.1).unwrap());
+        assert_normalized(&sinc(5));
+        assert_normalized(&exponential_decay(0.2).unwrap());
+        assert_normalized(&harmonics(&[(1., 0.), (0.5, 90.)]));
+        assert_normalized(&white_noise(42));
+        assert_normalized(&pink_noise(42));
+    }
+
+    #[test]
+    fn test_non_positive() {
+        let error = crate::Error::OutOfRange {
+            value: 0.,
+            min: 0.,
+            max: f64::INFINITY,
+        };
+
+        assert_eq!(gaussian_pulse(0.), Err(error.clone()));
+        assert_eq!(exponential_decay(0.), Err(error));
+        assert!(exponential_decay(-0.1).is_err());
+    }
+
+    #[test]
+    fn test_staircase() {
+        let data = staircase(3).unwrap();
+
+        assert_eq!(data[0], -1.);
+        assert_eq!(data[BUFFER_SIZE / 2], 0.);
+        assert_eq!(data[BUFFER_SIZE - 1], 1.);
+
+        assert_eq!(
+            staircase(1),
+            Err(crate::Error::OutOfRange {
+                value: 1.,
+                min: 2.,
+                max: f64::INFINITY,
+            })
+        );
+    }
+
+    #[test]
+    fn test_multi_tone() {
+        let data = multi_tone(&[Tone {
+            cycles: 1,
+            amplitude: 0.5,
+            phase: 90.,
+        }]);
+
+        assert!((data[0] - 1.).abs() < 1e-6);
+        assert!(data[BUFFER_SIZE / 4].abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_noise_seed() {
+        assert_eq!(white_noise(1), white_noise(1));
+        assert_ne!(white_noise(1), white_noise(2));
+    }
+}
@@ -0,0 +1,322 @@
+use crate::bode::BodeConfig;
+use crate::spectrum::fft::Complex;
+
+/**
+ * DUT between the reference input (IN1 by default) and the response input
+ * (IN2), the shunt resistor between the response input and the ground.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImpedanceConfig {
+    /**
+     * Use `start == end` and `steps == 1` for a single frequency.
+     */
+    pub sweep: BodeConfig,
+    /**
+     * Shunt resistor, in ohms.
+     */
+    pub shunt: f64,
+    pub compensation: Option<Compensation>,
+}
+
+impl std::default::Default for ImpedanceConfig {
+    fn default() -> Self {
+        Self {
+            sweep: BodeConfig::default(),
+            shunt: 1_000.,
+            compensation: None,
+        }
+    }
+}
+
+/**
+ * Open and short fixture impedances, one per sweep frequency.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Compensation {
+    pub open: Vec<Complex>,
+    pub short: Vec<Complex>,
+}
+
+impl Compensation {
+    /**
+     * From sweeps measured with the fixture open then shorted.
+     */
+    #[must_use]
+    pub fn new(open: &[Impedance], short: &[Impedance]) -> Self {
+        Self {
+            open: open.iter().map(|z| z.z).collect(),
+            short: short.iter().map(|z| z.z).collect(),
+        }
+    }
+}
+
+/**
+ * Remove the fixture `open` and `short` impedances from `measured`.
+ */
+#[must_use]
+pub fn compensate(measured: Complex, open: Complex, short: Complex) -> Complex {
+    let one = Complex::from(1.);
+    let admittance = one / (open - short);
+
+    (measured - short) / (one - (measured - short) * admittance)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Impedance {
+    /**
+     * In Hz.
+     */
+    pub frequency: f64,
+    /**
+     * In ohms.
+     */
+    pub z: Complex,
+}
+
+impl Impedance {
+    /**
+     * `reference` and `response` are the voltages on both sides of the DUT.
+     */
+    #[must_use]
+    pub fn new(frequency: f64, reference: Complex, response: Complex, shunt: f64) -> Self {
+        Self {
+            frequency,
+            z: ((reference - response) / response).scale(shunt),
+        }
+    }
+
+    fn omega(&self) -> f64 {
+        std::f64::consts::TAU * self.frequency
+    }
+
+    #[must_use]
+    pub fn magnitude(&self) -> f64 {
+        self.z.norm()
+    }
+
+    /**
+     * In degrees.
+     */
+    #[must_use]
+    pub fn phase(&self) -> f64 {
+        self.z.arg().to_degrees()
+    }
+
+    /**
+     * Series resistance R, in ohms.
+     */
+    #[must_use]
+    pub fn resistance(&self) -> f64 {
+        self.z.re
+    }
+
+    /**
+     * Series reactance X, in ohms.
+     */
+    #[must_use]
+    pub fn reactance(&self) -> f64 {
+        self.z.im
+    }
+
+    fn admittance(&self) -> Complex {
+        Complex::from(1.) / self.z
+    }
+
+    /**
+     * Parallel resistance, in ohms.
+     */
+    #[must_use]
+    pub fn parallel_resistance(&self) -> f64 {
+        1. / self.admittance().re
+    }
+
+    /**
+     * In henries.
+     */
+    #[must_use]
+    pub fn series_inductance(&self) -> f64 {
+        self.reactance() / self.omega()
+    }
+
+    /**
+     * In farads.
+     */
+    #[must_use]
+    pub fn series_capacitance(&self) -> f64 {
+        -1. / (self.omega() * self.reactance())
+    }
+
+    /**
+     * In henries.
+     */
+    #[must_use]
+    pub fn parallel_inductance(&self) -> f64 {
+        -1. / (self.omega() * self.admittance().im)
+    }
+
+    /**
+     * In farads.
+     */
+    #[must_use]
+    pub fn parallel_capacitance(&self) -> f64 {
+        self.admittance().im / self.omega()
+    }
+
+    /**
+     * Quality factor |X| / R.
+     */
+    #[must_use]
+    pub fn q(&self) -> f64 {
+        self.reactance().abs() / self.resistance()
+    }
+
+    /**
+     * Dissipation factor R / |X|.
+     */
+    #[must_use]
+    pub fn d(&self) -> f64 {
+        self.resistance() / self.reactance().abs()
+    }
+}
+
+impl crate::Redpitaya {
+    /**
+     * Measure the DUT impedance at each frequency of `config.sweep`.
+     */
+    pub fn impedance(&self, config: &ImpedanceConfig) -> Result<Vec<Impedance>, crate::Error> {
+        if let Some(compensation) = &config.compensation {
+            for len in [compensation.open.len(), compensation.short.len()] {
+                if len != config.sweep.steps {
+                    return Err(crate::Error::Length {
+                        expected: config.sweep.steps,
+                        actual: len,
+                    });
+                }
+            }
+        }
+
+        let impedances = self
+            .frequency_response(&config.sweep)?
+            .into_iter()
+            .enumerate()
+            .map(|(i, (frequency, ratio))| {
+                let mut impedance =
+                    Impedance::new(frequency, Complex::from(1.), ratio, config.shunt);
+
+                if let Some(compensation) = &config.compensation {
+                    impedance.z =
+                        compensate(impedance.z, compensation.open[i], compensation.short[i]);
+                }
+
+                impedance
+            })
+            .collect();
+
+        Ok(impedances)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::spectrum::fft::Complex;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() <= expected.abs() * 1e-9,
+            "{actual} != {expected}"
+        );
+    }
+
+    /**
+     * Voltages of a divider made of `z` and the `shunt`, driven with 1 V.
+     */
+    fn impedance(frequency: f64, z: Complex, shunt: f64) -> crate::impedance::Impedance {
+        let response = Complex::from(shunt) / (z + Complex::from(shunt));
+
+        crate::impedance::Impedance::new(frequency, Complex::from(1.), response, shunt)
+    }
+
+    #[test]
+    fn test_series_rc() {
+        // 100 Ω + 1 µF at 1 kHz
+        let x = -1. / (std::f64::consts::TAU * 1e3 * 1e-6);
+        let z = impedance(1e3, Complex::new(100., x), 1_000.);
+
+        assert_close(z.resistance(), 100.);
+        assert_close(z.reactance(), x);
+        assert_close(z.series_capacitance(), 1e-6);
+        assert_close(z.d(), 100. / x.abs());
+        assert_close(z.q(), x.abs() / 100.);
+        assert_close(z.magnitude(), 100f64.hypot(x));
+        assert!(z.phase() < 0.);
+    }
+
+    #[test]
+    fn test_parallel_rl() {
+        // 10 kΩ // 1 mH at 100 kHz
+        let (r, l, omega) = (10e3, 1e-3, std::f64::consts::TAU * 100e3);
+        let z = Complex::from(1.) / (Complex::from(1. / r) + Complex::new(0., -1. / (omega * l)));
+        let z = impedance(100e3, z, 100.);
+
+        assert_close(z.parallel_resistance(), r);
+        assert_close(z.parallel_inductance(), l);
+        assert!(z.series_inductance() > 0.);
+    }
+
+    #[test]
+    fn test_parallel_capacitance() {
+        let omega = std::f64::consts::TAU * 1e3;
+        let z = Complex::from(1.) / Complex::new(0., omega * 10e-9);
+        let z = impedance(1e3, z, 10e3);
+
+        assert_close(z.parallel_capacitance(), 10e-9);
+    }
+
+    #[test]
+    fn test_compensate() {
+        let dut = Complex::new(50., 20.);
+        let short = Complex::new(0.1, 0.05);
+        let open = Complex::new(1e6, -2e5);
+
+        // Fixture: short impedance in series with the DUT in parallel with
+        // the stray admittance
+        let one = Complex::from(1.);
+        let measured = short + one / (one / dut + one / (open - short));
+
+        let z = crate::impedance::compensate(measured, open, short);
+        assert!((z - dut).norm() < 1e-9);
+    }
+
+    #[test]
+    #[cfg(feature = "mock")]
+    fn test_impedance() {
+        let (_, rp) = crate::test::create_client();
+
+        let config = crate::impedance::ImpedanceConfig {
+            sweep: crate::bode::BodeConfig {
+                steps: 3,
+                timeout: std::time::Duration::from_millis(10),
+                ..Default::default()
+            },
+            compensation: Some(crate::impedance::Compensation {
+                open: vec![Complex::from(1e6); 3],
+                short: vec![Complex::from(0.1); 2],
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            rp.impedance(&config),
+            Err(crate::Error::Length {
+                expected: 3,
+                actual: 2,
+            })
+        );
+
+        let config = crate::impedance::ImpedanceConfig {
+            compensation: None,
+            ..config
+        };
+        assert_eq!(rp.impedance(&config), Err(crate::Error::Timeout));
+    }
+}
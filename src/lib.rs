@@ -3,13 +3,28 @@
 
 pub mod acquire;
 pub mod analog;
+pub mod autoset;
+pub mod bode;
 pub mod burst;
+pub mod capture;
+pub mod continuous;
+pub mod counter;
 pub mod data;
 pub mod digital;
+pub mod error;
+pub mod filter;
 pub mod general;
 pub mod generator;
+pub mod impedance;
+pub mod lockin;
+pub mod math;
+pub mod measure;
 pub mod socket;
+pub mod spectrum;
 pub mod trigger;
+pub mod waveform;
+
+pub use error::Error;
 
 trait Module {
     fn new(socket: socket::Socket) -> Self;
@@ -43,6 +58,13 @@ impl Redpitaya {
             trigger: trigger::Trigger::new(socket),
         }
     }
+
+    /**
+     * Select the board model instead of detecting it, see `General::board`.
+     */
+    pub fn set_board(&self, board: general::Board) {
+        self.general.set_board(board);
+    }
 }
 
 impl std::default::Default for Redpitaya {
@@ -62,19 +84,79 @@ mod test {
         (rx, crate::Redpitaya::new(addr))
     }
 
+    /**
+     * Each command uses its own connection, so commands sent in a row may be
+     * received in any order.
+     */
+    #[cfg(feature = "mock")]
+    pub fn assert_received(rx: &std::sync::mpsc::Receiver<String>, expected: &[&str]) {
+        let mut received = expected
+            .iter()
+            .map(|_| rx.recv().unwrap())
+            .collect::<Vec<_>>();
+        received.sort();
+
+        let mut expected = expected
+            .iter()
+            .map(|command| format!("{command}\r\n"))
+            .collect::<Vec<_>>();
+        expected.sort();
+
+        assert_eq!(received, expected);
+    }
+
+    /**
+     * `amplitude * sin(2π frequency t + phase)`, `len` samples at `sampling`
+     * Hz.
+     */
+    pub fn sine(amplitude: f64, frequency: f64, phase: f64, sampling: f64, len: usize) -> Vec<f64> {
+        (0..len)
+            .map(|n| {
+                amplitude * (std::f64::consts::TAU * frequency * n as f64 / sampling + phase).sin()
+            })
+            .collect()
+    }
+
+    /**
+     * Low gain channel of `source`.
+     */
+    pub fn channel(source: crate::acquire::Source, samples: Vec<f64>) -> crate::waveform::Channel {
+        crate::waveform::Channel {
+            source,
+            gain: crate::acquire::Gain::LV,
+            samples,
+        }
+    }
+
+    /**
+     * Waveform of a single IN1 channel, in volts at 125 MHz, triggered on
+     * the first sample.
+     */
+    pub fn waveform(samples: Vec<f64>) -> crate::waveform::Waveform {
+        crate::waveform::Waveform {
+            channels: vec![channel(crate::acquire::Source::IN1, samples)],
+            decimation: crate::acquire::Decimation::DEC_1,
+            trigger: 0,
+            units: crate::data::Unit::VOLTS,
+            timestamp: std::time::SystemTime::UNIX_EPOCH,
+        }
+    }
+
     pub fn launch_server() -> (String, std::sync::mpsc::Receiver<String>) {
         let addr = next_test_ip4();
         let listener = std::net::TcpListener::bind(addr.to_string()).unwrap();
 
         let (tx, rx) = std::sync::mpsc::channel();
+        let triggered = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
 
         std::thread::spawn(move || {
             loop {
                 if let Ok((mut stream, _)) = listener.accept() {
                     let tx = tx.clone();
+                    let triggered = triggered.clone();
 
                     std::thread::spawn(move || {
-                        handle_client(&mut stream, tx);
+                        handle_client(&mut stream, tx, &triggered);
                     });
                 }
             }
@@ -119,7 +201,11 @@ mod test {
             + 19600
     }
 
-    fn handle_client(stream: &mut std::net::TcpStream, tx: std::sync::mpsc::Sender<String>) {
+    fn handle_client(
+        stream: &mut std::net::TcpStream,
+        tx: std::sync::mpsc::Sender<String>,
+        triggered: &std::sync::atomic::AtomicBool,
+    ) {
         let mut message = String::new();
 
         loop {
@@ -129,7 +215,7 @@ mod test {
             message.push(buffer[0] as char);
 
             if buffer[0] == b'\n' {
-                match handle_message(message.clone()) {
+                match handle_message(message.clone(), triggered) {
                     Some(mut response) => {
                         response.push_str("\r\n");
                         stream.write_all(response.as_bytes()).unwrap();
@@ -145,55 +231,105 @@ mod test {
     }
 
     #[cfg(not(feature = "mock"))]
-    fn handle_message(message: String) -> Option<String> {
+    fn handle_message(message: String, _: &std::sync::atomic::AtomicBool) -> Option<String> {
         let socket = crate::socket::Socket::new("192.168.1.5:5000".to_owned());
 
         socket.send(message.clone())
     }
 
+    /**
+     * The external trigger fires as soon as it’s enabled, the other sources
+     * never do.
+     */
     #[cfg(feature = "mock")]
-    fn handle_message(message: String) -> Option<String> {
-        let mut response = match message.replace("\r\n", "").as_str() {
+    fn handle_message(
+        message: String,
+        triggered: &std::sync::atomic::AtomicBool,
+    ) -> Option<String> {
+        let message = message.replace("\r\n", "");
+
+        match message.as_str() {
+            "ACQ:TRIG EXT_PE" => {
+                triggered.store(true, std::sync::atomic::Ordering::SeqCst);
+                return None;
+            }
+            "ACQ:TRIG:STAT?" if triggered.load(std::sync::atomic::Ordering::SeqCst) => {
+                return Some("TD".to_owned());
+            }
+            _ => (),
+        }
+
+        // Full buffer
+        if message.ends_with(":DATA:STA:N? 1024,16384") || message == "ACQ:SOUR1:DATA?" {
+            let value = if message.starts_with("ACQ:SOUR1") {
+                "0.5"
+            } else {
+                "-0.5"
+            };
+
+            return Some(format!("{{{}}}", vec![value; 16_384].join(",")));
+        }
+
+        // Full arbitrary waveform
+        if message == "SOUR1:TRAC:DATA:DATA?" {
+            let mut trace = vec!["0"; 16_384];
+            trace[..3].copy_from_slice(&["1", "0.5", "0.2"]);
+
+            return Some(trace.join(","));
+        }
+
+        let response = match message.as_str() {
             "ACQ:DEC?" => "1",
             "ACQ:AVG?" => "ON",
             "ACQ:DATA:UNITS?" => "RAW",
             "ACQ:SOUR1:GAIN?" => "HV",
+            "ACQ:SOUR2:GAIN?" => "LV",
             "ACQ:SRAT?" => "125000000 Hz",
             "ACQ:WPOS?" => "1024",
             "ACQ:TPOS?" => "512",
             "ACQ:SOUR1:DATA:STA:END? 10,12" => "{123,231,-231}",
+            "ACQ:SOUR1:DATA:STA:END? 16383,1" => "{123,231,-231}",
             "ACQ:SOUR1:DATA:STA:N? 10,3" => "{1.2,3.2,-1.2}",
-            "ACQ:SOUR1:DATA?" => "{1.2,3.2,-1.2}",
+            "ACQ:SOUR1:DATA:STA:N? 0,3" => "{1.2,3.2,-1.2}",
+            "ACQ:SOUR1:DATA:STA:N? 510,3" => "{1.2,3.2,-1.2}",
+            "ACQ:SOUR2:DATA?" => "{1.2,3.2,-1.2}",
             "ACQ:SOUR1:DATA:OLD:N? 2" => "{3.2,-1.2}",
             "ACQ:SOUR1:DATA:LAT:N? 2" => "{1.2,3.2}",
             "ACQ:BUF:SIZE?" => "16384",
             "ACQ:TRIG:STAT?" => "WAIT",
+            "ACQ:TRIG:FILL?" => "1",
             "ACQ:TRIG:DLY?" => "2314",
-            "ACQ:TRIG:DLY:NS?" => "128ns",
+            "ACQ:TRIG:DLY:NS?" => "-128ns",
             "ACQ:TRIG:HYST?" => "0.75",
             "ACQ:TRIG:LEV?" => "0.4",
+            "ACQ:TRIG:EXT:LEV?" => "1.5",
+            "ACQ:TRIG:SOUR?" => "CH1_PE",
             "ANALOG:PIN? AIN1" => "1.34",
             "DIG:PIN? DIO0_N" => "1",
             "OUTPUT2:STATE?" => "ON",
+            "SYST:BRD:Name?" => "STEMlab 125-14 v1.1",
             "SOUR1:DCYC?" => "1.0",
             "SOUR1:FREQ:FIX?" => "1000",
             "SOUR2:FREQ:FIX?" => "8.82604e+06",
             "SOUR1:FUNC?" => "SINE",
+            "SOUR1:GAIN?" => "X5",
+            "SOUR1:LOAD?" => "L50",
             "SOUR1:PHAS?" => "180",
-            "SOUR1:TRAC:DATA:DATA?" => "1,0.5,0.2",
+            "SOUR2:TRAC:DATA:DATA?" => "1,0.5,0.2",
             "SOUR1:TRIG:SOUR?" => "BURST",
             "SOUR1:VOLT?" => "-0.5",
             "SOUR1:VOLT:OFFS?" => "0.3",
             "SOUR2:BURS:STAT?" => "BURST",
             "SOUR2:BURS:NCYC?" => "3",
+            "SOUR1:BURS:STAT?" => "CONTINUOUS",
+            "SOUR1:BURS:NCYC?" => "1",
             "SOUR1:BURS:NOR?" => "5",
+            "SOUR1:BURS:INT:PER?" => "1000",
             "SOUR2:BURS:INT:PER?" => "1000000",
             _ => return None,
         }
         .to_owned();
 
-        response.push_str("\r\n");
-
         Some(response)
     }
 }
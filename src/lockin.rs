@@ -0,0 +1,433 @@
+use crate::spectrum::fft;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Reference {
+    /**
+     * Internal reference at this frequency in Hz, usually the generator
+     * one. Its phase is 0 at the trigger, or at the first continuous block.
+     */
+    FREQUENCY(f64),
+    /**
+     * Reference measured on an input, its frequency and phase are estimated
+     * on each buffer, the phase over the largest whole number of periods.
+     */
+    CHANNEL(crate::acquire::Source),
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LockInConfig {
+    pub reference: Reference,
+    /**
+     * Demodulate at this multiple of the reference frequency.
+     */
+    pub harmonic: u32,
+    /**
+     * Low-pass filter time constant, in seconds.
+     */
+    pub time_constant: f64,
+    /**
+     * Number of cascaded RC filters, each adding 6 dB/octave.
+     */
+    pub order: usize,
+}
+
+impl std::default::Default for LockInConfig {
+    fn default() -> Self {
+        Self {
+            reference: Reference::FREQUENCY(1_000.),
+            harmonic: 1,
+            time_constant: 0.1,
+            order: 2,
+        }
+    }
+}
+
+/**
+ * Demodulated signal, in Vrms.
+ */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Output {
+    /**
+     * In phase component.
+     */
+    pub x: f64,
+    /**
+     * Quadrature component.
+     */
+    pub y: f64,
+}
+
+impl Output {
+    /**
+     * Magnitude.
+     */
+    #[must_use]
+    pub fn r(&self) -> f64 {
+        self.x.hypot(self.y)
+    }
+
+    /**
+     * Phase relative to the reference, in degrees.
+     */
+    #[must_use]
+    pub fn theta(&self) -> f64 {
+        self.y.atan2(self.x).to_degrees()
+    }
+}
+
+/**
+ * Software lock-in amplifier.
+ *
+ * Filters state is kept between buffers, so successive captures or blocks
+ * are processed as a single signal.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct LockIn {
+    config: LockInConfig,
+    x: Vec<f64>,
+    y: Vec<f64>,
+    /**
+     * Time of the next continuous block sample, in seconds.
+     */
+    time: f64,
+}
+
+impl LockIn {
+    /**
+     * The time constant must be positive and at least one filter stage is
+     * needed.
+     */
+    pub fn new(config: LockInConfig) -> Result<Self, crate::Error> {
+        if !(config.time_constant > 0. && config.time_constant.is_finite()) {
+            return Err(crate::Error::OutOfRange {
+                value: config.time_constant,
+                min: 0.,
+                max: f64::INFINITY,
+            });
+        }
+
+        if config.order == 0 {
+            return Err(crate::Error::OutOfRange {
+                value: 0.,
+                min: 1.,
+                max: f64::INFINITY,
+            });
+        }
+
+        Ok(Self {
+            config,
+            x: vec![0.; config.order],
+            y: vec![0.; config.order],
+            time: 0.,
+        })
+    }
+
+    pub fn reset(&mut self) {
+        self.x.fill(0.);
+        self.y.fill(0.);
+        self.time = 0.;
+    }
+
+    /**
+     * Demodulate a captured channel.
+     */
+    pub fn process_waveform(
+        &mut self,
+        waveform: &crate::waveform::Waveform,
+        source: crate::acquire::Source,
+    ) -> Result<Output, crate::Error> {
+        let samples = |source| {
+            waveform
+                .channel(source)
+                .map(|channel| channel.samples.as_slice())
+                .ok_or(crate::Error::Channel(source))
+        };
+        let interval = waveform.interval();
+
+        let (frequency, phase) = match self.config.reference {
+            Reference::FREQUENCY(frequency) => (
+                frequency,
+                std::f64::consts::TAU * frequency * waveform.time(0),
+            ),
+            Reference::CHANNEL(reference) => {
+                let reference = samples(reference)?;
+                let frequency = crate::measure::Measure::new(reference, interval).frequency()?;
+                (frequency, phase(reference, frequency * interval))
+            }
+        };
+
+        Ok(self.demodulate(samples(source)?, interval, frequency, phase))
+    }
+
+    /**
+     * Demodulate a continuous block, `interval` being the time between two
+     * samples.
+     *
+     * Blocks have a single channel, a `Reference::CHANNEL` fails with
+     * `Error::Channel`.
+     */
+    pub fn process_block(
+        &mut self,
+        block: &crate::continuous::Block,
+        interval: f64,
+    ) -> Result<Output, crate::Error> {
+        let frequency = match self.config.reference {
+            Reference::FREQUENCY(frequency) => frequency,
+            Reference::CHANNEL(source) => return Err(crate::Error::Channel(source)),
+        };
+
+        self.time += block.lost as f64 * interval;
+        let phase = std::f64::consts::TAU * frequency * self.time;
+        self.time += block.samples.len() as f64 * interval;
+
+        Ok(self.demodulate(&block.samples, interval, frequency, phase))
+    }
+
+    /**
+     * Multiply `samples` by the reference `cos(2π·f·t + phase)` at the
+     * harmonic and its quadrature, then low-pass filter.
+     */
+    fn demodulate(&mut self, samples: &[f64], interval: f64, frequency: f64, phase: f64) -> Output {
+        let harmonic = f64::from(self.config.harmonic);
+        let omega = std::f64::consts::TAU * frequency * harmonic * interval;
+        let alpha = 1. - (-interval / self.config.time_constant).exp();
+
+        for (n, sample) in samples.iter().enumerate() {
+            let angle = omega * n as f64 + harmonic * phase;
+
+            filter(&mut self.x, alpha, sample * angle.cos());
+            filter(&mut self.y, alpha, -sample * angle.sin());
+        }
+
+        self.output()
+    }
+
+    /**
+     * Current output, without new samples.
+     */
+    #[must_use]
+    pub fn output(&self) -> Output {
+        let last = |stages: &[f64]| stages.last().copied().unwrap_or(0.);
+
+        Output {
+            x: last(&self.x) * std::f64::consts::SQRT_2,
+            y: last(&self.y) * std::f64::consts::SQRT_2,
+        }
+    }
+}
+
+/**
+ * Phase of the cosine at `cycles` per sample in `samples`, estimated over the
+ * largest whole number of periods: the single bin DFT is biased otherwise.
+ */
+fn phase(samples: &[f64], cycles: f64) -> f64 {
+    let periods = (samples.len() as f64 * cycles).floor();
+    let len = ((periods / cycles).round() as usize).clamp(1, samples.len());
+
+    fft::single_bin(&samples[..len], cycles).arg()
+}
+
+fn filter(stages: &mut [f64], alpha: f64, mut input: f64) {
+    for stage in stages {
+        *stage += alpha * (input - *stage);
+        input = *stage;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    /**
+     * 1 MHz sampling, 10 kHz cosine at `phase`, 100 ms.
+     */
+    fn cosine(amplitude: f64, phase: f64) -> Vec<f64> {
+        crate::test::sine(
+            amplitude,
+            1e4,
+            phase + std::f64::consts::FRAC_PI_2,
+            1e6,
+            100_000,
+        )
+    }
+
+    fn config(reference: crate::lockin::Reference) -> crate::lockin::LockInConfig {
+        crate::lockin::LockInConfig {
+            reference,
+            time_constant: 1e-3,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        let config = config(crate::lockin::Reference::FREQUENCY(1e4));
+
+        assert_eq!(
+            crate::lockin::LockIn::new(crate::lockin::LockInConfig {
+                time_constant: 0.,
+                ..config
+            }),
+            Err(crate::Error::OutOfRange {
+                value: 0.,
+                min: 0.,
+                max: f64::INFINITY,
+            })
+        );
+        assert!(
+            crate::lockin::LockIn::new(crate::lockin::LockInConfig {
+                time_constant: f64::NAN,
+                ..config
+            })
+            .is_err()
+        );
+        assert_eq!(
+            crate::lockin::LockIn::new(crate::lockin::LockInConfig { order: 0, ..config }),
+            Err(crate::Error::OutOfRange {
+                value: 0.,
+                min: 1.,
+                max: f64::INFINITY,
+            })
+        );
+    }
+
+    #[test]
+    fn test_block() {
+        let mut lockin =
+            crate::lockin::LockIn::new(config(crate::lockin::Reference::FREQUENCY(1e4))).unwrap();
+        let samples = cosine(1., std::f64::consts::FRAC_PI_3);
+
+        let mut output = None;
+        for chunk in samples.chunks(10_000) {
+            let block = crate::continuous::Block {
+                samples: chunk.to_vec(),
+                lost: 0,
+                timestamp: std::time::SystemTime::UNIX_EPOCH,
+            };
+            output = Some(lockin.process_block(&block, 1e-6).unwrap());
+        }
+
+        let output = output.unwrap();
+        assert!((output.r() - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-3);
+        assert!((output.theta() - 60.).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_lost() {
+        let mut lockin =
+            crate::lockin::LockIn::new(config(crate::lockin::Reference::FREQUENCY(1e4))).unwrap();
+        let samples = cosine(1., 0.);
+
+        // 25 samples lost, a quarter of period
+        let block = crate::continuous::Block {
+            samples: samples[..50_000].to_vec(),
+            lost: 0,
+            timestamp: std::time::SystemTime::UNIX_EPOCH,
+        };
+        lockin.process_block(&block, 1e-6).unwrap();
+        let block = crate::continuous::Block {
+            samples: samples[50_025..].to_vec(),
+            lost: 25,
+            timestamp: std::time::SystemTime::UNIX_EPOCH,
+        };
+
+        let output = lockin.process_block(&block, 1e-6).unwrap();
+        assert!(output.theta().abs() < 0.1);
+    }
+
+    #[test]
+    fn test_harmonic() {
+        let mut lockin = crate::lockin::LockIn::new(crate::lockin::LockInConfig {
+            harmonic: 2,
+            ..config(crate::lockin::Reference::FREQUENCY(5e3))
+        })
+        .unwrap();
+        let block = crate::continuous::Block {
+            samples: cosine(0.5, 0.),
+            lost: 0,
+            timestamp: std::time::SystemTime::UNIX_EPOCH,
+        };
+
+        let output = lockin.process_block(&block, 1e-6).unwrap();
+        assert!((output.r() - 0.5 * std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_waveform() {
+        // 15.6 MHz sampling, ~122 kHz reference on IN1, signal on IN2
+        let frequency = 125e6 / 8. / 128.;
+        let channel = |source, amplitude, phase: f64| {
+            crate::test::channel(
+                source,
+                crate::test::sine(
+                    amplitude,
+                    1.,
+                    phase + std::f64::consts::FRAC_PI_2,
+                    128.,
+                    16_384,
+                ),
+            )
+        };
+        let waveform = crate::waveform::Waveform {
+            channels: vec![
+                channel(crate::acquire::Source::IN1, 1., 0.3),
+                channel(
+                    crate::acquire::Source::IN2,
+                    0.1,
+                    0.3 - std::f64::consts::FRAC_PI_2,
+                ),
+            ],
+            decimation: crate::acquire::Decimation::DEC_8,
+            trigger: 8_192,
+            ..crate::test::waveform(Vec::new())
+        };
+
+        let mut lockin = crate::lockin::LockIn::new(crate::lockin::LockInConfig {
+            time_constant: 1e-4,
+            ..config(crate::lockin::Reference::CHANNEL(
+                crate::acquire::Source::IN1,
+            ))
+        })
+        .unwrap();
+        let output = lockin
+            .process_waveform(&waveform, crate::acquire::Source::IN2)
+            .unwrap();
+        assert!((output.r() - 0.1 * std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-3);
+        assert!((output.theta() + 90.).abs() < 0.5);
+
+        // The internal reference is in phase at the trigger
+        let mut lockin = crate::lockin::LockIn::new(crate::lockin::LockInConfig {
+            time_constant: 1e-4,
+            ..config(crate::lockin::Reference::FREQUENCY(frequency))
+        })
+        .unwrap();
+        let output = lockin
+            .process_waveform(&waveform, crate::acquire::Source::IN1)
+            .unwrap();
+        assert!((output.theta() - 0.3f64.to_degrees()).abs() < 0.5);
+    }
+
+    #[test]
+    fn test_phase() {
+        // 3.4 periods, the whole buffer would be off by 0.027 rad
+        let samples = crate::test::sine(1., 3.4, 1. + std::f64::consts::FRAC_PI_2, 1_000., 1_000);
+
+        assert!((crate::lockin::phase(&samples, 3.4e-3) - 1.).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_block_channel_reference() {
+        let mut lockin = crate::lockin::LockIn::new(config(crate::lockin::Reference::CHANNEL(
+            crate::acquire::Source::IN1,
+        )))
+        .unwrap();
+        let block = crate::continuous::Block {
+            samples: Vec::new(),
+            lost: 0,
+            timestamp: std::time::SystemTime::UNIX_EPOCH,
+        };
+
+        assert_eq!(
+            lockin.process_block(&block, 1e-6),
+            Err(crate::Error::Channel(crate::acquire::Source::IN1))
+        );
+    }
+}
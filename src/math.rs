@@ -0,0 +1,540 @@
+use crate::acquire::{Gain, Source};
+use crate::waveform::{Channel, Waveform};
+
+/**
+ * Unit of a derived trace, as powers of the capture unit and seconds.
+ */
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Unit {
+    pub base: crate::data::Unit,
+    pub power: i32,
+    pub time: i32,
+}
+
+impl Unit {
+    #[must_use]
+    pub fn new(base: crate::data::Unit) -> Self {
+        Self {
+            base,
+            power: 1,
+            time: 0,
+        }
+    }
+}
+
+impl std::fmt::Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let base = match self.base {
+            crate::data::Unit::RAW => "LSB",
+            crate::data::Unit::VOLTS => "V",
+        };
+        let factor = |symbol: &str, power: i32| match power.abs() {
+            0 => None,
+            1 => Some(symbol.to_string()),
+            power => Some(format!("{symbol}^{power}")),
+        };
+        let factors = |sign: i32| {
+            [(base, self.power), ("s", self.time)]
+                .into_iter()
+                .filter(|(_, power)| power.signum() == sign)
+                .filter_map(|(symbol, power)| factor(symbol, power))
+                .collect::<Vec<_>>()
+        };
+
+        let (numerator, denominator) = (factors(1), factors(-1));
+        let numerator = if numerator.is_empty() {
+            "1".to_string()
+        } else {
+            numerator.join("·")
+        };
+
+        if denominator.is_empty() {
+            write!(f, "{numerator}")
+        } else {
+            write!(f, "{numerator}/{}", denominator.join("·"))
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Operation {
+    ADD,
+    SUBTRACT,
+    MULTIPLY,
+    /**
+     * Samples divided by 0 are infinite or NaN.
+     */
+    DIVIDE,
+}
+
+impl std::fmt::Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Operation::ADD => "+",
+            Operation::SUBTRACT => "-",
+            Operation::MULTIPLY => "×",
+            Operation::DIVIDE => "/",
+        };
+
+        write!(f, "{s}")
+    }
+}
+
+/**
+ * Channel or derived trace.
+ *
+ * The samples are kept as the `source` channel of a single channel
+ * waveform, the one of the first operand for a derived trace, so it is
+ * measured, analysed and filtered like a captured channel.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trace {
+    /**
+     * Source or expression, e.g. `IN1 - IN2`.
+     */
+    pub label: String,
+    pub unit: Unit,
+    pub source: Source,
+    pub waveform: Waveform,
+}
+
+impl Trace {
+    #[must_use]
+    pub fn samples(&self) -> &[f64] {
+        self.waveform
+            .channel(self.source)
+            .map(|channel| channel.samples.as_slice())
+            .unwrap_or_default()
+    }
+
+    /**
+     * Both traces must come from the same capture: same sampling rate and
+     * length.
+     */
+    fn check(&self, other: &Self) -> Result<(), crate::Error> {
+        if self.waveform.decimation != other.waveform.decimation {
+            return Err(crate::Error::SamplingRate {
+                expected: self.waveform.sampling_rate(),
+                actual: other.waveform.sampling_rate(),
+            });
+        }
+
+        if self.samples().len() != other.samples().len() {
+            return Err(crate::Error::Length {
+                expected: self.samples().len(),
+                actual: other.samples().len(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Label in parentheses if it is an expression.
+     */
+    fn operand(&self) -> String {
+        if self.label.contains(' ') {
+            format!("({})", self.label)
+        } else {
+            self.label.clone()
+        }
+    }
+
+    fn derive(&self, label: String, unit: Unit, samples: Vec<f64>) -> Self {
+        let channel = Channel {
+            source: self.source,
+            gain: self
+                .waveform
+                .channel(self.source)
+                .map_or(Gain::LV, |channel| channel.gain),
+            samples,
+        };
+
+        Self {
+            label,
+            unit,
+            source: self.source,
+            waveform: Waveform {
+                channels: vec![channel],
+                ..self.waveform.clone()
+            },
+        }
+    }
+
+    /**
+     * Sample by sample `self operation other`.
+     *
+     * Both traces must derive from captures in the same units, and be in
+     * the same unit for sums and differences.
+     */
+    pub fn math(&self, operation: Operation, other: &Self) -> Result<Self, crate::Error> {
+        self.check(other)?;
+
+        if self.unit.base != other.unit.base {
+            return Err(crate::Error::Units {
+                expected: self.unit.base,
+                actual: other.unit.base,
+            });
+        }
+
+        if matches!(operation, Operation::ADD | Operation::SUBTRACT) && self.unit != other.unit {
+            return Err(crate::Error::Dimension {
+                expected: self.unit,
+                actual: other.unit,
+            });
+        }
+
+        let f = match operation {
+            Operation::ADD => |a: f64, b: f64| a + b,
+            Operation::SUBTRACT => |a: f64, b: f64| a - b,
+            Operation::MULTIPLY => |a: f64, b: f64| a * b,
+            Operation::DIVIDE => |a: f64, b: f64| a / b,
+        };
+        let unit = match operation {
+            Operation::ADD | Operation::SUBTRACT => self.unit,
+            Operation::MULTIPLY => Unit {
+                power: self.unit.power + other.unit.power,
+                time: self.unit.time + other.unit.time,
+                ..self.unit
+            },
+            Operation::DIVIDE => Unit {
+                power: self.unit.power - other.unit.power,
+                time: self.unit.time - other.unit.time,
+                ..self.unit
+            },
+        };
+
+        let samples = self
+            .samples()
+            .iter()
+            .zip(other.samples())
+            .map(|(a, b)| f(*a, *b))
+            .collect();
+
+        Ok(self.derive(
+            format!("{} {operation} {}", self.operand(), other.operand()),
+            unit,
+            samples,
+        ))
+    }
+
+    /**
+     * `gain × samples + offset`, e.g. to apply a probe attenuation or a
+     * current sense resistor. Update `unit` if the gain changes it.
+     */
+    #[must_use]
+    pub fn scale(&self, gain: f64, offset: f64) -> Self {
+        self.derive(
+            format!("{gain} × {} + {offset}", self.operand()),
+            self.unit,
+            self.samples().iter().map(|x| gain * x + offset).collect(),
+        )
+    }
+
+    #[must_use]
+    pub fn abs(&self) -> Self {
+        self.derive(
+            format!("|{}|", self.label),
+            self.unit,
+            self.samples().iter().map(|x| x.abs()).collect(),
+        )
+    }
+
+    /**
+     * Central differences, one sided at both ends.
+     */
+    #[must_use]
+    pub fn derivative(&self) -> Self {
+        let samples = self.samples();
+        let n = samples.len();
+        let interval = self.waveform.interval();
+
+        let derivative = (0..n)
+            .map(|i| {
+                let (before, after) = (i.saturating_sub(1), (i + 1).min(n - 1));
+
+                if before == after {
+                    0.
+                } else {
+                    (samples[after] - samples[before]) / ((after - before) as f64 * interval)
+                }
+            })
+            .collect();
+
+        self.derive(
+            format!("d{}/dt", self.operand()),
+            Unit {
+                time: self.unit.time - 1,
+                ..self.unit
+            },
+            derivative,
+        )
+    }
+
+    /**
+     * Cumulative trapezoidal integral from the first sample.
+     */
+    #[must_use]
+    pub fn integral(&self) -> Self {
+        let samples = self.samples();
+        let interval = self.waveform.interval();
+        let mut sum = 0.;
+
+        let integral = samples
+            .iter()
+            .enumerate()
+            .map(|(i, x)| {
+                if i > 0 {
+                    sum += (samples[i - 1] + x) / 2. * interval;
+                }
+                sum
+            })
+            .collect();
+
+        self.derive(
+            format!("∫{}", self.operand()),
+            Unit {
+                time: self.unit.time + 1,
+                ..self.unit
+            },
+            integral,
+        )
+    }
+
+    /**
+     * `(self, other)` pairs for an XY plot.
+     */
+    pub fn xy(&self, other: &Self) -> Result<Vec<(f64, f64)>, crate::Error> {
+        self.check(other)?;
+
+        Ok(self
+            .samples()
+            .iter()
+            .copied()
+            .zip(other.samples().iter().copied())
+            .collect())
+    }
+}
+
+impl Waveform {
+    #[must_use]
+    pub fn trace(&self, source: Source) -> Option<Trace> {
+        self.channel(source).map(|channel| Trace {
+            label: format!("{source:?}"),
+            unit: Unit::new(self.units),
+            source,
+            waveform: Waveform {
+                channels: vec![channel.clone()],
+                decimation: self.decimation,
+                trigger: self.trigger,
+                units: self.units,
+                timestamp: self.timestamp,
+            },
+        })
+    }
+
+    /**
+     * `IN1 operation IN2`, e.g. a differential measurement with
+     * `Operation::SUBTRACT`.
+     */
+    pub fn math(&self, operation: Operation) -> Result<Trace, crate::Error> {
+        let trace = |source| self.trace(source).ok_or(crate::Error::Channel(source));
+
+        trace(Source::IN1)?.math(operation, &trace(Source::IN2)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    fn waveform() -> crate::waveform::Waveform {
+        crate::waveform::Waveform {
+            channels: vec![
+                crate::test::channel(crate::acquire::Source::IN1, vec![1., 2., 4., -2.]),
+                crate::test::channel(crate::acquire::Source::IN2, vec![2., 2., 1., 1.]),
+            ],
+            trigger: 1,
+            ..crate::test::waveform(Vec::new())
+        }
+    }
+
+    #[test]
+    fn test_math() {
+        let waveform = waveform();
+
+        let difference = waveform.math(crate::math::Operation::SUBTRACT).unwrap();
+        assert_eq!(difference.label, "IN1 - IN2");
+        assert_eq!(difference.samples(), [-1., 0., 3., -3.]);
+        assert_eq!(difference.unit.to_string(), "V");
+        assert_eq!(difference.source, crate::acquire::Source::IN1);
+        assert_eq!(difference.waveform.channels.len(), 1);
+        assert_eq!(difference.waveform.trigger, 1);
+        assert_eq!(
+            difference.waveform.decimation,
+            crate::acquire::Decimation::DEC_1
+        );
+
+        let sum = waveform.math(crate::math::Operation::ADD).unwrap();
+        assert_eq!(sum.samples(), [3., 4., 5., -1.]);
+
+        let product = waveform.math(crate::math::Operation::MULTIPLY).unwrap();
+        assert_eq!(product.samples(), [2., 4., 4., -2.]);
+        assert_eq!(product.unit.to_string(), "V^2");
+
+        let ratio = waveform.math(crate::math::Operation::DIVIDE).unwrap();
+        assert_eq!(ratio.samples(), [0.5, 1., 4., -2.]);
+        assert_eq!(ratio.unit.to_string(), "1");
+    }
+
+    #[test]
+    fn test_check() {
+        let waveform = waveform();
+        let in1 = waveform.trace(crate::acquire::Source::IN1).unwrap();
+
+        let short = crate::waveform::Waveform {
+            channels: vec![crate::test::channel(crate::acquire::Source::IN2, vec![1.])],
+            ..waveform.clone()
+        }
+        .trace(crate::acquire::Source::IN2)
+        .unwrap();
+        assert_eq!(
+            in1.math(crate::math::Operation::ADD, &short),
+            Err(crate::Error::Length {
+                expected: 4,
+                actual: 1,
+            })
+        );
+
+        let slow = crate::waveform::Waveform {
+            decimation: crate::acquire::Decimation::DEC_8,
+            ..waveform.clone()
+        }
+        .trace(crate::acquire::Source::IN2)
+        .unwrap();
+        assert_eq!(
+            in1.xy(&slow),
+            Err(crate::Error::SamplingRate {
+                expected: crate::acquire::SamplingRate::RATE_125MHz,
+                actual: crate::acquire::SamplingRate::RATE_15_6MHz,
+            })
+        );
+
+        let raw = crate::waveform::Waveform {
+            units: crate::data::Unit::RAW,
+            ..waveform.clone()
+        }
+        .trace(crate::acquire::Source::IN2)
+        .unwrap();
+        assert_eq!(
+            in1.math(crate::math::Operation::MULTIPLY, &raw),
+            Err(crate::Error::Units {
+                expected: crate::data::Unit::VOLTS,
+                actual: crate::data::Unit::RAW,
+            })
+        );
+
+        let derivative = in1.derivative();
+        assert_eq!(
+            in1.math(crate::math::Operation::SUBTRACT, &derivative),
+            Err(crate::Error::Dimension {
+                expected: in1.unit,
+                actual: derivative.unit,
+            })
+        );
+        assert!(
+            in1.math(crate::math::Operation::DIVIDE, &derivative)
+                .is_ok()
+        );
+
+        let single = crate::waveform::Waveform {
+            channels: vec![waveform.channels[0].clone()],
+            ..waveform
+        };
+        assert_eq!(
+            single.math(crate::math::Operation::ADD),
+            Err(crate::Error::Channel(crate::acquire::Source::IN2))
+        );
+    }
+
+    #[test]
+    fn test_unary() {
+        let in1 = waveform().trace(crate::acquire::Source::IN1).unwrap();
+
+        let scaled = in1.scale(10., 1.);
+        assert_eq!(scaled.samples(), [11., 21., 41., -19.]);
+        assert_eq!(scaled.derivative().label, "d(10 × IN1 + 1)/dt");
+
+        let abs = in1.abs();
+        assert_eq!(abs.label, "|IN1|");
+        assert_eq!(abs.samples(), [1., 2., 4., 2.]);
+
+        // 8 ns per sample
+        let derivative = in1.derivative();
+        assert_eq!(derivative.unit.to_string(), "V/s");
+        assert_eq!(
+            derivative.samples(),
+            [1. / 8e-9, 1.5 / 8e-9, -2. / 8e-9, -6. / 8e-9]
+        );
+
+        let integral = in1.integral();
+        assert_eq!(integral.unit.to_string(), "V·s");
+        let expected = [0., 1.5 * 8e-9, 4.5 * 8e-9, 5.5 * 8e-9];
+        assert!(
+            integral
+                .samples()
+                .iter()
+                .zip(expected)
+                .all(|(actual, expected)| (actual - expected).abs() < 1e-20)
+        );
+
+        let unit = crate::math::Unit {
+            base: crate::data::Unit::RAW,
+            power: -2,
+            time: -1,
+        };
+        assert_eq!(unit.to_string(), "1/LSB^2·s");
+    }
+
+    #[test]
+    fn test_xy() {
+        let waveform = waveform();
+        let in1 = waveform.trace(crate::acquire::Source::IN1).unwrap();
+        let in2 = waveform.trace(crate::acquire::Source::IN2).unwrap();
+
+        assert_eq!(in1.xy(&in2).unwrap()[2], (4., 1.));
+        assert_eq!(in1.waveform.points(in1.source).unwrap()[0], (-8e-9, 1.));
+    }
+
+    #[test]
+    fn test_measure() {
+        // Differential sine: IN1 and IN2 in phase opposition around 1 V
+        let sine = |amplitude| {
+            crate::test::sine(amplitude, 1., 0., 100., 1_000)
+                .into_iter()
+                .map(|x| 1. + x)
+                .collect()
+        };
+        let waveform = crate::waveform::Waveform {
+            channels: vec![
+                crate::test::channel(crate::acquire::Source::IN1, sine(0.5)),
+                crate::test::channel(crate::acquire::Source::IN2, sine(-0.5)),
+            ],
+            ..waveform()
+        };
+
+        let difference = waveform.math(crate::math::Operation::SUBTRACT).unwrap();
+        let measure = difference.waveform.measure(difference.source).unwrap();
+        assert!((measure.vpp().unwrap() - 2.).abs() < 1e-3);
+        assert!(measure.mean().unwrap().abs() < 1e-9);
+        assert!((measure.frequency().unwrap() - 1.25e6).abs() < 1.);
+
+        let spectrum = difference
+            .waveform
+            .spectrum(difference.source, crate::spectrum::Window::HANN)
+            .unwrap();
+        assert_eq!(spectrum.peaks(1)[0].bin, 10);
+
+        let mut filter = crate::filter::MovingAverage::new(100);
+        let filtered = difference.waveform.filter(&mut filter, false).unwrap();
+        assert!(filtered.channels[0].samples[999].abs() < 1e-9);
+    }
+}
@@ -0,0 +1,517 @@
+use crate::trigger::software::Edge;
+
+/**
+ * Why a measurement couldn’t be done.
+ */
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Invalid {
+    /**
+     * No samples.
+     */
+    EMPTY,
+    /**
+     * The signal doesn’t move, top and base levels are the same.
+     */
+    FLAT,
+    /**
+     * Not enough edges in the capture.
+     */
+    EDGES,
+}
+
+impl std::fmt::Display for Invalid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let display = match self {
+            Invalid::EMPTY => "no samples",
+            Invalid::FLAT => "flat signal",
+            Invalid::EDGES => "not enough edges",
+        };
+
+        write!(f, "{display}")
+    }
+}
+
+/**
+ * A measured value or why it couldn’t be measured.
+ */
+pub type Measurement = Result<f64, Invalid>;
+
+/**
+ * All measurements of a channel, in volts, seconds, hertz or percent.
+ */
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Measurements {
+    pub vpp: Measurement,
+    pub max: Measurement,
+    pub min: Measurement,
+    pub mean: Measurement,
+    pub rms: Measurement,
+    pub rms_ac: Measurement,
+    pub frequency: Measurement,
+    pub period: Measurement,
+    pub duty_cycle: Measurement,
+    pub rise_time: Measurement,
+    pub fall_time: Measurement,
+    pub overshoot: Measurement,
+    pub positive_width: Measurement,
+    pub negative_width: Measurement,
+}
+
+/**
+ * Measurements on a sampled signal.
+ *
+ * Timings use the crossing of the middle level between base and top, with a
+ * 10% hysteresis, interpolated between samples.
+ */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Measure<'a> {
+    samples: &'a [f64],
+    interval: f64,
+}
+
+impl<'a> Measure<'a> {
+    /**
+     * `interval` is the time between two samples, in seconds.
+     */
+    #[must_use]
+    pub fn new(samples: &'a [f64], interval: f64) -> Self {
+        Self { samples, interval }
+    }
+
+    pub fn max(&self) -> Measurement {
+        self.samples
+            .iter()
+            .copied()
+            .reduce(f64::max)
+            .ok_or(Invalid::EMPTY)
+    }
+
+    pub fn min(&self) -> Measurement {
+        self.samples
+            .iter()
+            .copied()
+            .reduce(f64::min)
+            .ok_or(Invalid::EMPTY)
+    }
+
+    /**
+     * Peak to peak voltage.
+     */
+    pub fn vpp(&self) -> Measurement {
+        Ok(self.max()? - self.min()?)
+    }
+
+    pub fn mean(&self) -> Measurement {
+        self.average(|x| x)
+    }
+
+    /**
+     * RMS including the DC component.
+     */
+    pub fn rms(&self) -> Measurement {
+        self.average(|x| x * x).map(f64::sqrt)
+    }
+
+    /**
+     * RMS of the signal without its mean.
+     */
+    pub fn rms_ac(&self) -> Measurement {
+        let mean = self.mean()?;
+
+        self.average(|x| (x - mean).powi(2)).map(f64::sqrt)
+    }
+
+    /**
+     * High level of the signal: histogram mode of the samples above the
+     * middle.
+     */
+    pub fn top(&self) -> Measurement {
+        let max = self.max()?;
+        let middle = (max + self.min()?) / 2.;
+
+        mode(
+            self.samples.iter().copied().filter(|x| *x >= middle),
+            middle,
+            max,
+        )
+    }
+
+    /**
+     * Low level of the signal: histogram mode of the samples below the
+     * middle.
+     */
+    pub fn base(&self) -> Measurement {
+        let min = self.min()?;
+        let middle = (self.max()? + min) / 2.;
+
+        mode(
+            self.samples.iter().copied().filter(|x| *x <= middle),
+            min,
+            middle,
+        )
+    }
+
+    pub fn amplitude(&self) -> Measurement {
+        Ok(self.top()? - self.base()?)
+    }
+
+    /**
+     * Mean time between two rising edges.
+     */
+    pub fn period(&self) -> Measurement {
+        let rising = self.rising_edges()?;
+
+        match (rising.first(), rising.last()) {
+            (Some(first), Some(last)) if rising.len() >= 2 => {
+                Ok((last - first) / (rising.len() - 1) as f64 * self.interval)
+            }
+            _ => Err(Invalid::EDGES),
+        }
+    }
+
+    pub fn frequency(&self) -> Measurement {
+        self.period().map(|period| 1. / period)
+    }
+
+    /**
+     * Positions, in samples, of the rising crossings of the middle level.
+     */
+    pub fn rising_edges(&self) -> Result<Vec<f64>, Invalid> {
+        Ok(self
+            .edges()?
+            .into_iter()
+            .filter(|(_, edge)| *edge == Edge::RISING)
+            .map(|(position, _)| position)
+            .collect())
+    }
+
+    /**
+     * Ratio of the positive width to the period, in percent.
+     */
+    pub fn duty_cycle(&self) -> Measurement {
+        Ok(self.positive_width()? / self.period()? * 100.)
+    }
+
+    /**
+     * Mean width of the high pulses.
+     */
+    pub fn positive_width(&self) -> Measurement {
+        self.width(Edge::RISING)
+    }
+
+    /**
+     * Mean width of the low pulses.
+     */
+    pub fn negative_width(&self) -> Measurement {
+        self.width(Edge::FALLING)
+    }
+
+    /**
+     * Mean 10% to 90% transition time of the rising edges.
+     */
+    pub fn rise_time(&self) -> Measurement {
+        let (base, top) = (self.base()?, self.top()?);
+        let low = base + (top - base) * 0.1;
+        let high = base + (top - base) * 0.9;
+
+        self.transition(Edge::RISING, |samples, position| {
+            transition(samples, position, low, high)
+        })
+    }
+
+    /**
+     * Mean 90% to 10% transition time of the falling edges.
+     */
+    pub fn fall_time(&self) -> Measurement {
+        let (base, top) = (self.base()?, self.top()?);
+        let low = base + (top - base) * 0.1;
+        let high = base + (top - base) * 0.9;
+        let inverted = self.samples.iter().map(|x| -x).collect::<Vec<_>>();
+
+        self.transition(Edge::FALLING, |_, position| {
+            transition(&inverted, position, -high, -low)
+        })
+    }
+
+    /**
+     * Overshoot above the top level, in percent of the amplitude.
+     */
+    pub fn overshoot(&self) -> Measurement {
+        let amplitude = self.amplitude()?;
+
+        if amplitude == 0. {
+            return Err(Invalid::FLAT);
+        }
+
+        Ok((self.max()? - self.top()?) / amplitude * 100.)
+    }
+
+    #[must_use]
+    pub fn all(&self) -> Measurements {
+        Measurements {
+            vpp: self.vpp(),
+            max: self.max(),
+            min: self.min(),
+            mean: self.mean(),
+            rms: self.rms(),
+            rms_ac: self.rms_ac(),
+            frequency: self.frequency(),
+            period: self.period(),
+            duty_cycle: self.duty_cycle(),
+            rise_time: self.rise_time(),
+            fall_time: self.fall_time(),
+            overshoot: self.overshoot(),
+            positive_width: self.positive_width(),
+            negative_width: self.negative_width(),
+        }
+    }
+
+    fn average<F: Fn(f64) -> f64>(&self, f: F) -> Measurement {
+        if self.samples.is_empty() {
+            return Err(Invalid::EMPTY);
+        }
+
+        Ok(self.samples.iter().map(|x| f(*x)).sum::<f64>() / self.samples.len() as f64)
+    }
+
+    /**
+     * Crossings of the middle level, alternating rising and falling.
+     */
+    fn edges(&self) -> Result<Vec<(f64, Edge)>, Invalid> {
+        let (base, top) = (self.base()?, self.top()?);
+
+        if top == base {
+            return Err(Invalid::FLAT);
+        }
+
+        let middle = (top + base) / 2.;
+        let hysteresis = (top - base) * 0.1;
+        let mut edges = Vec::new();
+        let mut high = None;
+        let mut crossing = 0.;
+
+        for (index, x) in self.samples.iter().enumerate() {
+            if let Some(previous) = index.checked_sub(1).map(|i| self.samples[i])
+                && (previous - middle) * (x - middle) <= 0.
+                && previous != *x
+            {
+                crossing = (index - 1) as f64 + (middle - previous) / (x - previous);
+            }
+
+            if *x > middle + hysteresis && high != Some(true) {
+                if high == Some(false) {
+                    edges.push((crossing, Edge::RISING));
+                }
+                high = Some(true);
+            } else if *x < middle - hysteresis && high != Some(false) {
+                if high == Some(true) {
+                    edges.push((crossing, Edge::FALLING));
+                }
+                high = Some(false);
+            }
+        }
+
+        Ok(edges)
+    }
+
+    fn width(&self, start: Edge) -> Measurement {
+        let widths = self
+            .edges()?
+            .windows(2)
+            .filter(|w| w[0].1 == start)
+            .map(|w| w[1].0 - w[0].0)
+            .collect::<Vec<_>>();
+
+        if widths.is_empty() {
+            return Err(Invalid::EDGES);
+        }
+
+        Ok(widths.iter().sum::<f64>() / widths.len() as f64 * self.interval)
+    }
+
+    fn transition<F>(&self, edge: Edge, f: F) -> Measurement
+    where
+        F: Fn(&[f64], f64) -> Option<f64>,
+    {
+        let durations = self
+            .edges()?
+            .into_iter()
+            .filter(|(_, e)| *e == edge)
+            .filter_map(|(position, _)| f(self.samples, position))
+            .collect::<Vec<_>>();
+
+        if durations.is_empty() {
+            return Err(Invalid::EDGES);
+        }
+
+        Ok(durations.iter().sum::<f64>() / durations.len() as f64 * self.interval)
+    }
+}
+
+/**
+ * Duration, in samples, of the rising transition from `low` to `high`
+ * around `position`.
+ */
+fn transition(samples: &[f64], position: f64, low: f64, high: f64) -> Option<f64> {
+    let index = position.floor() as usize;
+    let crossing =
+        |i: usize, level: f64| i as f64 + (level - samples[i]) / (samples[i + 1] - samples[i]);
+
+    let start = (0..=index).rev().find(|i| samples[*i] <= low)?;
+    let end = (index + 1..samples.len()).find(|i| samples[*i] >= high)?;
+
+    Some(crossing(end - 1, high) - crossing(start, low))
+}
+
+/**
+ * Most frequent value between `min` and `max`: mean of the values in the
+ * fullest of `BINS` bins.
+ */
+fn mode<I: Iterator<Item = f64>>(values: I, min: f64, max: f64) -> Measurement {
+    const BINS: usize = 256;
+
+    let width = (max - min) / BINS as f64;
+    let mut bins = [(0_usize, 0.); BINS];
+
+    for value in values {
+        let bin = if width > 0. {
+            (((value - min) / width) as usize).min(BINS - 1)
+        } else {
+            0
+        };
+
+        bins[bin].0 += 1;
+        bins[bin].1 += value;
+    }
+
+    bins.into_iter()
+        .filter(|(count, _)| *count > 0)
+        .max_by_key(|(count, _)| *count)
+        .map(|(count, sum)| sum / count as f64)
+        .ok_or(Invalid::EMPTY)
+}
+
+impl crate::waveform::Waveform {
+    /**
+     * Measurements on a channel, using the waveform sampling rate.
+     *
+     * The samples must be in volts, raw ADC values aren’t converted.
+     */
+    pub fn measure(&self, source: crate::acquire::Source) -> Result<Measure<'_>, crate::Error> {
+        if self.units != crate::data::Unit::VOLTS {
+            return Err(crate::Error::Units {
+                expected: crate::data::Unit::VOLTS,
+                actual: self.units,
+            });
+        }
+
+        let channel = self.channel(source).ok_or(crate::Error::Channel(source))?;
+
+        Ok(Measure::new(&channel.samples, self.interval()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    /**
+     * 10 periods of 20 samples between 0 and 1, with an overshoot of 0.2 on
+     * the first high sample.
+     */
+    fn square() -> Vec<f64> {
+        let period = [
+            0., 0.25, 0.5, 0.75, 1.2, 1., 1., 1., 1., 1., 0.75, 0.5, 0.25, 0., 0., 0., 0., 0., 0.,
+            0.,
+        ];
+
+        period.iter().copied().cycle().take(200).collect()
+    }
+
+    #[test]
+    fn test_levels() {
+        let samples = square();
+        let measure = crate::measure::Measure::new(&samples, 1e-6);
+
+        assert_eq!(measure.max(), Ok(1.2));
+        assert_eq!(measure.min(), Ok(0.));
+        assert_eq!(measure.vpp(), Ok(1.2));
+        assert_eq!(measure.top(), Ok(1.));
+        assert_eq!(measure.base(), Ok(0.));
+        assert!((measure.overshoot().unwrap() - 20.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rms() {
+        let samples = crate::test::sine(2., 1., 0., 100., 1_000)
+            .into_iter()
+            .map(|x| 1. + x)
+            .collect::<Vec<_>>();
+        let measure = crate::measure::Measure::new(&samples, 1e-6);
+
+        assert!((measure.mean().unwrap() - 1.).abs() < 1e-9);
+        assert!((measure.rms_ac().unwrap() - 2f64.sqrt()).abs() < 1e-9);
+        assert!((measure.rms().unwrap() - 3f64.sqrt()).abs() < 1e-9);
+        assert!((measure.frequency().unwrap() - 10e3).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_timing() {
+        let samples = square();
+        let measure = crate::measure::Measure::new(&samples, 1e-6);
+
+        assert!((measure.period().unwrap() - 20e-6).abs() < 1e-12);
+        assert!((measure.frequency().unwrap() - 50e3).abs() < 1e-6);
+        assert!((measure.positive_width().unwrap() - 9e-6).abs() < 1e-12);
+        assert!((measure.negative_width().unwrap() - 11e-6).abs() < 1e-12);
+        assert!((measure.duty_cycle().unwrap() - 45.).abs() < 1e-9);
+        // 10% at 0.4 sample, 90% at 3 + 1/3
+        assert!((measure.rise_time().unwrap() - (3. + 1. / 3. - 0.4) * 1e-6).abs() < 1e-12);
+        // 90% at 9.4, 10% at 12.6
+        assert!((measure.fall_time().unwrap() - 3.2e-6).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let measure = crate::measure::Measure::new(&[], 1e-6);
+        assert_eq!(measure.vpp(), Err(crate::measure::Invalid::EMPTY));
+
+        let samples = [1.; 10];
+        let measure = crate::measure::Measure::new(&samples, 1e-6);
+        assert_eq!(measure.rms(), Ok(1.));
+        assert_eq!(measure.frequency(), Err(crate::measure::Invalid::FLAT));
+
+        let samples = [0., 0., 1., 1., 1.];
+        let measure = crate::measure::Measure::new(&samples, 1e-6);
+        assert_eq!(measure.period(), Err(crate::measure::Invalid::EDGES));
+        assert_eq!(measure.fall_time(), Err(crate::measure::Invalid::EDGES));
+        assert!(measure.rise_time().is_ok());
+    }
+
+    #[test]
+    fn test_waveform() {
+        let waveform = crate::waveform::Waveform {
+            decimation: crate::acquire::Decimation::DEC_8,
+            ..crate::test::waveform(square())
+        };
+
+        let measurements = waveform.measure(crate::acquire::Source::IN1).unwrap().all();
+        assert!((measurements.period.unwrap() - 20. * 64e-9).abs() < 1e-15);
+        assert_eq!(
+            waveform.measure(crate::acquire::Source::IN2).err(),
+            Some(crate::Error::Channel(crate::acquire::Source::IN2))
+        );
+
+        let waveform = crate::waveform::Waveform {
+            units: crate::data::Unit::RAW,
+            ..waveform
+        };
+        assert_eq!(
+            waveform.measure(crate::acquire::Source::IN1).err(),
+            Some(crate::Error::Units {
+                expected: crate::data::Unit::VOLTS,
+                actual: crate::data::Unit::RAW,
+            })
+        );
+    }
+}
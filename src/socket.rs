@@ -3,12 +3,19 @@ use std::io::prelude::*;
 #[derive(Clone, Debug)]
 pub struct Socket {
     addr: String,
+    /**
+     * Board model shared by all the modules, see `General::board`.
+     */
+    pub(crate) board: std::sync::Arc<std::sync::Mutex<Option<crate::general::Board>>>,
 }
 
 impl Socket {
     #[must_use]
     pub fn new(addr: String) -> Self {
-        Self { addr }
+        Self {
+            addr,
+            board: std::sync::Arc::default(),
+        }
     }
 
     pub fn send<D>(&self, command: D) -> Option<String>
@@ -30,10 +37,51 @@ impl Socket {
         }
     }
 
-    fn receive(stream: std::net::TcpStream) -> String {
+    /**
+     * Send a query and parse its response, kept in the error if it can’t be
+     * parsed.
+     */
+    pub(crate) fn query<T, D>(&self, query: D) -> Result<T, crate::Error>
+    where
+        T: std::str::FromStr,
+        D: std::fmt::Display,
+    {
+        let response = self.send(query).unwrap_or_default();
+
+        response
+            .parse()
+            .map_err(|_| crate::Error::Response(response))
+    }
+
+    /**
+     * Send all queries on the same connection before reading the responses.
+     */
+    pub fn send_all<D>(&self, queries: &[D]) -> Vec<String>
+    where
+        D: std::fmt::Display,
+    {
+        let mut stream =
+            std::net::TcpStream::connect(self.addr.clone()).expect("Unable to connect");
+
         let mut message = String::new();
+        for query in queries {
+            log::info!("> {query}");
+            message.push_str(&format!("{query}\r\n"));
+        }
+        stream.write_all(message.as_bytes()).unwrap();
+
         let mut reader = std::io::BufReader::new(stream);
 
+        queries.iter().map(|_| Self::read(&mut reader)).collect()
+    }
+
+    fn receive(stream: std::net::TcpStream) -> String {
+        Self::read(&mut std::io::BufReader::new(stream))
+    }
+
+    fn read(reader: &mut std::io::BufReader<std::net::TcpStream>) -> String {
+        let mut message = String::new();
+
         reader.read_line(&mut message).unwrap();
 
         let message = message.trim_end_matches("\r\n");
@@ -0,0 +1,583 @@
+pub mod fft;
+pub mod quality;
+
+use crate::acquire::SamplingRate;
+use fft::Complex;
+
+/**
+ * Load used for dBm, in ohms.
+ */
+pub const LOAD: f64 = 50.;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Window {
+    RECTANGULAR,
+    HANN,
+    HAMMING,
+    /**
+     * 4 terms, low leakage.
+     */
+    BLACKMAN_HARRIS,
+    /**
+     * Accurate amplitude between bins.
+     */
+    FLAT_TOP,
+}
+
+impl Window {
+    /**
+     * Periodic window of `len` points.
+     */
+    #[must_use]
+    pub fn coefficients(self, len: usize) -> Vec<f64> {
+        let terms: &[f64] = match self {
+            Window::RECTANGULAR => &[1.],
+            Window::HANN => &[0.5, 0.5],
+            Window::HAMMING => &[0.54, 0.46],
+            Window::BLACKMAN_HARRIS => &[0.358_75, 0.488_29, 0.141_28, 0.011_68],
+            Window::FLAT_TOP => &[
+                0.215_578_95,
+                0.416_631_58,
+                0.277_263_158,
+                0.083_578_947,
+                0.006_947_368,
+            ],
+        };
+
+        (0..len)
+            .map(|i| {
+                let x = std::f64::consts::TAU * i as f64 / len as f64;
+
+                terms
+                    .iter()
+                    .enumerate()
+                    .map(|(k, a)| {
+                        let sign = if k % 2 == 0 { 1. } else { -1. };
+
+                        sign * a * (k as f64 * x).cos()
+                    })
+                    .sum()
+            })
+            .collect()
+    }
+
+    /**
+     * Half width of the main lobe, in bins.
+     */
+    #[must_use]
+    pub fn lobe(self) -> usize {
+        match self {
+            Window::RECTANGULAR => 1,
+            Window::HANN | Window::HAMMING => 2,
+            Window::BLACKMAN_HARRIS => 4,
+            Window::FLAT_TOP => 5,
+        }
+    }
+
+    /**
+     * Equivalent noise bandwidth, in bins.
+     */
+    #[must_use]
+    pub fn enbw(self) -> f64 {
+        match self {
+            Window::RECTANGULAR => 1.,
+            Window::HANN => 1.5,
+            Window::HAMMING => 1.362_8,
+            Window::BLACKMAN_HARRIS => 2.004_4,
+            Window::FLAT_TOP => 3.770_2,
+        }
+    }
+}
+
+impl std::fmt::Display for Window {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let display = match self {
+            Window::RECTANGULAR => "Rectangular",
+            Window::HANN => "Hann",
+            Window::HAMMING => "Hamming",
+            Window::BLACKMAN_HARRIS => "Blackman-Harris",
+            Window::FLAT_TOP => "Flat top",
+        };
+
+        write!(f, "{display}")
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Unit {
+    VRMS,
+    DBV,
+    /**
+     * Power into `LOAD`.
+     */
+    DBM,
+}
+
+impl Unit {
+    /**
+     * Convert a RMS voltage.
+     */
+    #[must_use]
+    pub fn convert(self, vrms: f64) -> f64 {
+        match self {
+            Unit::VRMS => vrms,
+            Unit::DBV => 20. * vrms.log10(),
+            Unit::DBM => 10. * (vrms * vrms / LOAD / 1e-3).log10(),
+        }
+    }
+}
+
+impl std::fmt::Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let display = match self {
+            Unit::VRMS => "Vrms",
+            Unit::DBV => "dBV",
+            Unit::DBM => "dBm",
+        };
+
+        write!(f, "{display}")
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Peak {
+    pub bin: usize,
+    /**
+     * In Hz.
+     */
+    pub frequency: f64,
+    /**
+     * In Vrms.
+     */
+    pub magnitude: f64,
+}
+
+/**
+ * Single sided spectrum.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Spectrum {
+    /**
+     * Frequency step between two bins, in Hz.
+     */
+    pub resolution: f64,
+    /**
+     * RMS amplitude of each bin, corrected for the window gain. The first
+     * bin is the mean value.
+     */
+    pub magnitude: Vec<f64>,
+    /**
+     * Phase of each bin, in radians.
+     */
+    pub phase: Vec<f64>,
+    pub window: Window,
+}
+
+impl Spectrum {
+    #[must_use]
+    pub fn new(samples: &[f64], sampling_rate: SamplingRate, window: Window) -> Self {
+        Self::with_frequency(samples, sampling_rate.frequency(), window)
+    }
+
+    /**
+     * Spectrum of samples taken at `frequency` Hz.
+     */
+    #[must_use]
+    pub fn with_frequency(samples: &[f64], frequency: f64, window: Window) -> Self {
+        let n = samples.len();
+
+        if n == 0 {
+            return Self {
+                resolution: 0.,
+                magnitude: Vec::new(),
+                phase: Vec::new(),
+                window,
+            };
+        }
+
+        let coefficients = window.coefficients(n);
+        let gain = coefficients.iter().sum::<f64>();
+
+        let input = samples
+            .iter()
+            .zip(&coefficients)
+            .map(|(x, w)| Complex::from(x * w))
+            .collect::<Vec<_>>();
+
+        let bins = fft::fft(&input)
+            .into_iter()
+            .take(n / 2 + 1)
+            .collect::<Vec<_>>();
+
+        let magnitude = bins
+            .iter()
+            .enumerate()
+            .map(|(k, x)| {
+                // DC and Nyquist bins aren’t folded
+                if k == 0 || 2 * k == n {
+                    x.norm() / gain
+                } else {
+                    x.norm() / gain * std::f64::consts::SQRT_2
+                }
+            })
+            .collect();
+
+        Self {
+            resolution: frequency / n as f64,
+            magnitude,
+            phase: bins.iter().map(|x| x.arg()).collect(),
+            window,
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.magnitude.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    /**
+     * Frequency of `bin`, in Hz.
+     */
+    #[must_use]
+    pub fn frequency(&self, bin: usize) -> f64 {
+        bin as f64 * self.resolution
+    }
+
+    #[must_use]
+    pub fn frequencies(&self) -> Vec<f64> {
+        (0..self.len()).map(|bin| self.frequency(bin)).collect()
+    }
+
+    /**
+     * Nearest bin of `frequency`.
+     */
+    #[must_use]
+    pub fn bin(&self, frequency: f64) -> usize {
+        ((frequency / self.resolution).round() as usize).min(self.len().saturating_sub(1))
+    }
+
+    #[must_use]
+    pub fn values(&self, unit: Unit) -> Vec<f64> {
+        self.magnitude.iter().map(|x| unit.convert(*x)).collect()
+    }
+
+    /**
+     * The `count` highest local maxima, DC excluded, highest first.
+     */
+    #[must_use]
+    pub fn peaks(&self, count: usize) -> Vec<Peak> {
+        let m = &self.magnitude;
+
+        let mut peaks = (1..self.len())
+            .filter(|k| m[*k] > m[k - 1] && m.get(k + 1).is_none_or(|next| m[*k] >= *next))
+            .map(|bin| Peak {
+                bin,
+                frequency: self.frequency(bin),
+                magnitude: m[bin],
+            })
+            .collect::<Vec<_>>();
+
+        peaks.sort_by(|a, b| b.magnitude.total_cmp(&a.magnitude));
+        peaks.truncate(count);
+        peaks
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Averaging {
+    /**
+     * Power average of all spectra.
+     */
+    LINEAR,
+    /**
+     * Power average where the new spectrum weights `alpha`, in ]0, 1].
+     */
+    EXPONENTIAL(f64),
+    /**
+     * Maximum of each bin.
+     */
+    PEAK_HOLD,
+}
+
+/**
+ * Combine spectra of successive captures.
+ *
+ * The phase is the one of the last spectrum.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct Average {
+    averaging: Averaging,
+    count: usize,
+    spectrum: Option<Spectrum>,
+}
+
+impl Average {
+    pub fn new(averaging: Averaging) -> Result<Self, crate::Error> {
+        if let Averaging::EXPONENTIAL(alpha) = averaging
+            && !(alpha > 0. && alpha <= 1.)
+        {
+            return Err(crate::Error::OutOfRange {
+                value: alpha,
+                min: 0.,
+                max: 1.,
+            });
+        }
+
+        Ok(Self {
+            averaging,
+            count: 0,
+            spectrum: None,
+        })
+    }
+
+    /**
+     * Add a spectrum computed with the same window, resolution and length
+     * as the previous ones.
+     */
+    pub fn add(&mut self, spectrum: Spectrum) -> Result<(), crate::Error> {
+        let Some(average) = &mut self.spectrum else {
+            self.count = 1;
+            self.spectrum = Some(spectrum);
+
+            return Ok(());
+        };
+
+        if average.window != spectrum.window {
+            return Err(crate::Error::Window {
+                expected: average.window,
+                actual: spectrum.window,
+            });
+        }
+
+        if average.resolution != spectrum.resolution {
+            return Err(crate::Error::Resolution {
+                expected: average.resolution,
+                actual: spectrum.resolution,
+            });
+        }
+
+        if average.len() != spectrum.len() {
+            return Err(crate::Error::Length {
+                expected: average.len(),
+                actual: spectrum.len(),
+            });
+        }
+
+        let count = self.count as f64;
+
+        for (a, x) in average.magnitude.iter_mut().zip(&spectrum.magnitude) {
+            *a = match self.averaging {
+                Averaging::LINEAR => ((*a * *a * count + x * x) / (count + 1.)).sqrt(),
+                Averaging::EXPONENTIAL(alpha) => ((1. - alpha) * *a * *a + alpha * x * x).sqrt(),
+                Averaging::PEAK_HOLD => a.max(*x),
+            };
+        }
+
+        average.phase = spectrum.phase;
+        self.count += 1;
+
+        Ok(())
+    }
+
+    /**
+     * Number of spectra added.
+     */
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    #[must_use]
+    pub fn spectrum(&self) -> Option<&Spectrum> {
+        self.spectrum.as_ref()
+    }
+
+    pub fn reset(&mut self) {
+        self.count = 0;
+        self.spectrum = None;
+    }
+}
+
+impl crate::waveform::Waveform {
+    /**
+     * Spectrum of a channel, using the waveform sampling rate.
+     */
+    #[must_use]
+    pub fn spectrum(&self, source: crate::acquire::Source, window: Window) -> Option<Spectrum> {
+        self.channel(source)
+            .map(|channel| Spectrum::new(&channel.samples, self.sampling_rate(), window))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn test_window() {
+        let hann = crate::spectrum::Window::HANN.coefficients(4);
+        let expected = [0., 0.5, 1., 0.5];
+
+        assert!(
+            hann.iter()
+                .zip(expected)
+                .all(|(a, b)| (a - b).abs() < 1e-12)
+        );
+        assert!(
+            crate::spectrum::Window::RECTANGULAR
+                .coefficients(3)
+                .iter()
+                .all(|x| *x == 1.)
+        );
+    }
+
+    #[test]
+    fn test_spectrum() {
+        let samples = crate::test::sine(2., 64., 0., 1_024., 1_024)
+            .into_iter()
+            .map(|x| x + 0.5)
+            .collect::<Vec<_>>();
+        let spectrum = crate::spectrum::Spectrum::new(
+            &samples,
+            crate::acquire::SamplingRate::RATE_1_9kHz,
+            crate::spectrum::Window::HANN,
+        );
+
+        assert_eq!(spectrum.len(), 513);
+        assert!((spectrum.resolution - 125e6 / 65_536. / 1_024.).abs() < 1e-12);
+        assert!((spectrum.magnitude[0] - 0.5).abs() < 1e-9);
+        assert!((spectrum.magnitude[64] - std::f64::consts::SQRT_2).abs() < 1e-9);
+        assert!(spectrum.magnitude[200] < 1e-9);
+    }
+
+    #[test]
+    fn test_empty() {
+        let waveform = crate::test::waveform(Vec::new());
+        let spectrum = waveform
+            .spectrum(crate::acquire::Source::IN1, crate::spectrum::Window::HANN)
+            .unwrap();
+
+        assert_eq!(spectrum.len(), 0);
+        assert!(spectrum.peaks(1).is_empty());
+    }
+
+    #[test]
+    fn test_flat_top() {
+        // Between two bins
+        let samples = crate::test::sine(1., 50.5, 0., 1_000., 1_000);
+        let spectrum = crate::spectrum::Spectrum::with_frequency(
+            &samples,
+            1_000.,
+            crate::spectrum::Window::FLAT_TOP,
+        );
+
+        let peak = spectrum.peaks(1)[0];
+        assert!(peak.bin == 50 || peak.bin == 51);
+        assert!((peak.magnitude - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_peaks() {
+        let samples = crate::test::sine(1., 10., 0., 256., 256)
+            .iter()
+            .zip(crate::test::sine(0.1, 30., 0., 256., 256))
+            .map(|(a, b)| a + b)
+            .collect::<Vec<_>>();
+        let spectrum = crate::spectrum::Spectrum::with_frequency(
+            &samples,
+            256.,
+            crate::spectrum::Window::BLACKMAN_HARRIS,
+        );
+
+        let peaks = spectrum.peaks(2);
+        assert_eq!(peaks.len(), 2);
+        assert_eq!(peaks[0].frequency, 10.);
+        assert_eq!(peaks[1].frequency, 30.);
+        assert_eq!(spectrum.bin(29.8), 30);
+    }
+
+    #[test]
+    fn test_unit() {
+        assert_eq!(crate::spectrum::Unit::DBV.convert(1.), 0.);
+        assert_eq!(crate::spectrum::Unit::DBV.convert(0.1), -20.);
+        assert!((crate::spectrum::Unit::DBM.convert(0.223_606_8) - 0.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_average() {
+        let spectrum = |magnitude: Vec<f64>| crate::spectrum::Spectrum {
+            resolution: 1.,
+            phase: vec![0.; magnitude.len()],
+            magnitude,
+            window: crate::spectrum::Window::HANN,
+        };
+
+        let mut average =
+            crate::spectrum::Average::new(crate::spectrum::Averaging::LINEAR).unwrap();
+        average.add(spectrum(vec![1., 3.])).unwrap();
+        average.add(spectrum(vec![7., 1.])).unwrap();
+        assert_eq!(average.count(), 2);
+        assert_eq!(average.spectrum().unwrap().magnitude, vec![5., 5f64.sqrt()]);
+        assert_eq!(
+            average.add(spectrum(vec![1.])),
+            Err(crate::Error::Length {
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert_eq!(
+            average.add(crate::spectrum::Spectrum {
+                resolution: 2.,
+                ..spectrum(vec![1., 3.])
+            }),
+            Err(crate::Error::Resolution {
+                expected: 1.,
+                actual: 2.
+            })
+        );
+        assert_eq!(
+            average.add(crate::spectrum::Spectrum {
+                window: crate::spectrum::Window::FLAT_TOP,
+                ..spectrum(vec![1., 3.])
+            }),
+            Err(crate::Error::Window {
+                expected: crate::spectrum::Window::HANN,
+                actual: crate::spectrum::Window::FLAT_TOP,
+            })
+        );
+        assert_eq!(average.count(), 2);
+
+        assert_eq!(
+            crate::spectrum::Average::new(crate::spectrum::Averaging::EXPONENTIAL(0.)),
+            Err(crate::Error::OutOfRange {
+                value: 0.,
+                min: 0.,
+                max: 1.,
+            })
+        );
+        assert!(
+            crate::spectrum::Average::new(crate::spectrum::Averaging::EXPONENTIAL(f64::NAN))
+                .is_err()
+        );
+
+        let mut average =
+            crate::spectrum::Average::new(crate::spectrum::Averaging::EXPONENTIAL(0.5)).unwrap();
+        average.add(spectrum(vec![1.])).unwrap();
+        average.add(spectrum(vec![7.])).unwrap();
+        assert_eq!(average.spectrum().unwrap().magnitude, vec![5.]);
+
+        let mut average =
+            crate::spectrum::Average::new(crate::spectrum::Averaging::PEAK_HOLD).unwrap();
+        average.add(spectrum(vec![1., 3.])).unwrap();
+        average.add(spectrum(vec![7., 1.])).unwrap();
+        assert_eq!(average.spectrum().unwrap().magnitude, vec![7., 3.]);
+
+        average.reset();
+        assert!(average.spectrum().is_none());
+    }
+}
@@ -0,0 +1,323 @@
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    #[must_use]
+    pub fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    /**
+     * `norm · e^(i·arg)`
+     */
+    #[must_use]
+    pub fn from_polar(norm: f64, arg: f64) -> Self {
+        Self::new(norm * arg.cos(), norm * arg.sin())
+    }
+
+    #[must_use]
+    pub fn norm(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    /**
+     * Argument in radians, between -π and π.
+     */
+    #[must_use]
+    pub fn arg(self) -> f64 {
+        self.im.atan2(self.re)
+    }
+
+    #[must_use]
+    pub fn conj(self) -> Self {
+        Self::new(self.re, -self.im)
+    }
+
+    #[must_use]
+    pub fn scale(self, factor: f64) -> Self {
+        Self::new(self.re * factor, self.im * factor)
+    }
+
+    /**
+     * Principal square root.
+     */
+    #[must_use]
+    pub fn sqrt(self) -> Self {
+        let norm = self.norm();
+        let re = ((norm + self.re) / 2.).sqrt();
+        let im = ((norm - self.re) / 2.).sqrt();
+
+        Self::new(re, if self.im < 0. { -im } else { im })
+    }
+}
+
+impl std::convert::From<f64> for Complex {
+    fn from(re: f64) -> Self {
+        Self::new(re, 0.)
+    }
+}
+
+impl std::ops::Add for Complex {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.re + other.re, self.im + other.im)
+    }
+}
+
+impl std::ops::Sub for Complex {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.re - other.re, self.im - other.im)
+    }
+}
+
+impl std::ops::Mul for Complex {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+}
+
+impl std::ops::Div for Complex {
+    type Output = Self;
+
+    fn div(self, other: Self) -> Self {
+        let norm = other.re * other.re + other.im * other.im;
+
+        (self * other.conj()).scale(1. / norm)
+    }
+}
+
+/**
+ * Discrete Fourier transform, without normalisation.
+ *
+ * Radix-2 for power of two lengths, Bluestein algorithm otherwise.
+ */
+#[must_use]
+pub fn fft(input: &[Complex]) -> Vec<Complex> {
+    if input.is_empty() {
+        return Vec::new();
+    }
+
+    if input.len().is_power_of_two() {
+        let mut data = input.to_vec();
+        radix2(&mut data, false);
+        data
+    } else {
+        bluestein(input)
+    }
+}
+
+/**
+ * Inverse transform, normalised so `ifft(fft(x)) == x`.
+ */
+#[must_use]
+pub fn ifft(input: &[Complex]) -> Vec<Complex> {
+    let n = input.len() as f64;
+    let conj = input.iter().map(|x| x.conj()).collect::<Vec<_>>();
+
+    fft(&conj)
+        .into_iter()
+        .map(|x| x.conj().scale(1. / n))
+        .collect()
+}
+
+/**
+ * Single bin DFT at `frequency` cycles per sample, scaled to the peak
+ * amplitude: a cosine of amplitude `a` and phase `φ` gives `a·e^(iφ)`.
+ *
+ * Exact when `samples` holds a whole number of periods.
+ */
+#[must_use]
+pub fn single_bin(samples: &[f64], frequency: f64) -> Complex {
+    let sum = samples
+        .iter()
+        .enumerate()
+        .fold(Complex::default(), |acc, (n, x)| {
+            let angle = -std::f64::consts::TAU * frequency * n as f64;
+
+            acc + Complex::from_polar(*x, angle)
+        });
+
+    sum.scale(2. / samples.len() as f64)
+}
+
+/**
+ * In place iterative Cooley-Tukey, `data.len()` must be a power of two.
+ */
+fn radix2(data: &mut [Complex], inverse: bool) {
+    let n = data.len();
+
+    if n <= 1 {
+        return;
+    }
+
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let sign = if inverse { 1. } else { -1. };
+    let mut len = 2;
+
+    while len <= n {
+        let step = Complex::from_polar(1., sign * std::f64::consts::TAU / len as f64);
+
+        for chunk in data.chunks_mut(len) {
+            let mut w = Complex::new(1., 0.);
+
+            for k in 0..len / 2 {
+                let even = chunk[k];
+                let odd = chunk[k + len / 2] * w;
+
+                chunk[k] = even + odd;
+                chunk[k + len / 2] = even - odd;
+                w = w * step;
+            }
+        }
+
+        len *= 2;
+    }
+}
+
+/**
+ * Arbitrary length transform as a convolution computed with power of two
+ * transforms.
+ */
+fn bluestein(input: &[Complex]) -> Vec<Complex> {
+    let n = input.len();
+    let m = (2 * n - 1).next_power_of_two();
+
+    // k² mod 2n keeps the angle accurate for large k
+    let chirp = (0..n)
+        .map(|k| {
+            let k2 = (k as u128 * k as u128 % (2 * n as u128)) as f64;
+
+            Complex::from_polar(1., -std::f64::consts::PI * k2 / n as f64)
+        })
+        .collect::<Vec<_>>();
+
+    let mut a = vec![Complex::default(); m];
+    for k in 0..n {
+        a[k] = input[k] * chirp[k];
+    }
+
+    let mut b = vec![Complex::default(); m];
+    b[0] = chirp[0].conj();
+    for k in 1..n {
+        b[k] = chirp[k].conj();
+        b[m - k] = chirp[k].conj();
+    }
+
+    radix2(&mut a, false);
+    radix2(&mut b, false);
+
+    let mut c = a.iter().zip(&b).map(|(a, b)| *a * *b).collect::<Vec<_>>();
+    radix2(&mut c, true);
+
+    (0..n)
+        .map(|k| c[k].scale(1. / m as f64) * chirp[k])
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::spectrum::fft::Complex;
+
+    fn dft(input: &[Complex]) -> Vec<Complex> {
+        let n = input.len();
+
+        (0..n)
+            .map(|k| {
+                input
+                    .iter()
+                    .enumerate()
+                    .fold(Complex::default(), |acc, (j, x)| {
+                        let angle = -std::f64::consts::TAU * (j * k) as f64 / n as f64;
+
+                        acc + *x * Complex::from_polar(1., angle)
+                    })
+            })
+            .collect()
+    }
+
+    fn assert_close(actual: &[Complex], expected: &[Complex]) {
+        assert_eq!(actual.len(), expected.len());
+
+        for (a, e) in actual.iter().zip(expected) {
+            assert!((*a - *e).norm() < 1e-9, "{a:?} != {e:?}");
+        }
+    }
+
+    fn input(n: usize) -> Vec<Complex> {
+        (0..n)
+            .map(|i| Complex::new((i as f64 * 0.7).sin(), (i as f64 * 1.3).cos()))
+            .collect()
+    }
+
+    #[test]
+    fn test_radix2() {
+        let input = input(64);
+
+        assert_close(&crate::spectrum::fft::fft(&input), &dft(&input));
+    }
+
+    #[test]
+    fn test_bluestein() {
+        for n in [1, 3, 12, 100] {
+            let input = input(n);
+
+            assert_close(&crate::spectrum::fft::fft(&input), &dft(&input));
+        }
+    }
+
+    #[test]
+    fn test_empty() {
+        assert!(crate::spectrum::fft::fft(&[]).is_empty());
+        assert!(crate::spectrum::fft::ifft(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_ifft() {
+        let input = input(30);
+        let output = crate::spectrum::fft::ifft(&crate::spectrum::fft::fft(&input));
+
+        assert_close(&output, &input);
+    }
+
+    #[test]
+    fn test_single_bin() {
+        let samples = (0..100)
+            .map(|n| 0.5 * (std::f64::consts::TAU * 0.03 * n as f64 + 1.).cos() + 0.2)
+            .collect::<Vec<_>>();
+        let bin = crate::spectrum::fft::single_bin(&samples, 0.03);
+
+        assert!((bin.norm() - 0.5).abs() < 1e-9);
+        assert!((bin.arg() - 1.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_complex() {
+        let a = Complex::new(1., 2.);
+        let b = Complex::new(3., -1.);
+
+        assert_eq!(a * b, Complex::new(5., 5.));
+        assert_close(&[a * b / b], &[a]);
+        assert_eq!(Complex::new(0., 2.).arg(), std::f64::consts::FRAC_PI_2);
+        assert_eq!(Complex::new(3., 4.).norm(), 5.);
+        assert_eq!(Complex::new(-4., 0.).sqrt(), Complex::new(0., 2.));
+        assert_eq!(Complex::new(3., -4.).sqrt(), Complex::new(2., -1.));
+    }
+}
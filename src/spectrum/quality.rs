@@ -0,0 +1,299 @@
+use crate::measure::Invalid;
+use crate::spectrum::{Spectrum, Window};
+
+/**
+ * Number of periods captured by `Redpitaya::characterize`.
+ */
+const PERIODS: f64 = 16.;
+
+/**
+ * Dynamic performances of a sine, ratios in dB.
+ */
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quality {
+    /**
+     * Fundamental frequency, in Hz.
+     */
+    pub frequency: f64,
+    /**
+     * Fundamental amplitude, in Vrms.
+     */
+    pub amplitude: f64,
+    /**
+     * Total harmonic distortion, relative to the fundamental.
+     */
+    pub thd: f64,
+    /**
+     * Signal to noise ratio, harmonics excluded.
+     */
+    pub snr: f64,
+    /**
+     * Signal to noise and distortion ratio.
+     */
+    pub sinad: f64,
+    /**
+     * Effective number of bits, computed from the SINAD.
+     */
+    pub enob: f64,
+    /**
+     * Spurious free dynamic range, between the fundamental and the highest
+     * other bin.
+     */
+    pub sfdr: f64,
+}
+
+impl Quality {
+    /**
+     * Analyse `spectrum` around the `fundamental` frequency, the highest
+     * peak if `None`, with `harmonics` harmonics after the fundamental.
+     *
+     * Harmonics above the Nyquist frequency are folded back, the spectrum
+     * is assumed to come from an even number of samples. The noise of the
+     * bins used by DC, fundamental and harmonics is extrapolated from the
+     * other ones.
+     */
+    pub fn new(
+        spectrum: &Spectrum,
+        fundamental: Option<f64>,
+        harmonics: usize,
+    ) -> Result<Self, Invalid> {
+        let len = spectrum.len();
+        let width = spectrum.window.lobe();
+        let m = &spectrum.magnitude;
+
+        if len <= 2 * width + 1 {
+            return Err(Invalid::EMPTY);
+        }
+
+        let highest = |range: std::ops::RangeInclusive<usize>| {
+            range.max_by(|a, b| m[*a].total_cmp(&m[*b])).unwrap_or(0)
+        };
+        let lobe = |bin: usize| bin.saturating_sub(width)..=(bin + width).min(len - 1);
+
+        let bin = match fundamental {
+            Some(frequency) => highest(lobe(spectrum.bin(frequency))),
+            None => highest(width + 1..=len - 1),
+        };
+
+        if bin <= width || m[bin] == 0. {
+            return Err(Invalid::FLAT);
+        }
+
+        let enbw = spectrum.window.enbw();
+        let mut used = vec![false; len];
+
+        power(m, &mut used, lobe(0));
+        let signal = power(m, &mut used, lobe(bin)) / enbw;
+        let spur = (0..len)
+            .filter(|k| !used[*k])
+            .map(|k| m[k])
+            .fold(0., f64::max);
+
+        let sampling = 2. * (len - 1) as f64 * spectrum.resolution;
+        let distortion = (2..=harmonics + 1)
+            .map(|h| {
+                let frequency = (h as f64 * spectrum.frequency(bin)) % sampling;
+                let frequency = frequency.min(sampling - frequency);
+
+                power(m, &mut used, lobe(spectrum.bin(frequency))) / enbw
+            })
+            .sum::<f64>();
+
+        let free = used.iter().filter(|used| !**used).count();
+        let noise = if free == 0 {
+            0.
+        } else {
+            power(m, &mut used, 0..=len - 1) / enbw * len as f64 / free as f64
+        };
+
+        let sinad = 10. * (signal / (noise + distortion)).log10();
+
+        Ok(Self {
+            frequency: spectrum.frequency(bin),
+            amplitude: signal.sqrt(),
+            thd: 10. * (distortion / signal).log10(),
+            snr: 10. * (signal / noise).log10(),
+            sinad,
+            enob: (sinad - 1.76) / 6.02,
+            sfdr: 20. * (m[bin] / spur).log10(),
+        })
+    }
+
+    /**
+     * THD as a percentage of the fundamental.
+     */
+    #[must_use]
+    pub fn thd_percent(&self) -> f64 {
+        100. * 10f64.powf(self.thd / 20.)
+    }
+}
+
+/**
+ * Sum of the squared magnitudes of the bins not used yet, marking them used.
+ */
+fn power(magnitude: &[f64], used: &mut [bool], range: std::ops::RangeInclusive<usize>) -> f64 {
+    range
+        .filter(|k| !std::mem::replace(&mut used[*k], true))
+        .map(|k| magnitude[k] * magnitude[k])
+        .sum()
+}
+
+impl crate::waveform::Waveform {
+    /**
+     * Quality of a channel, analysed with a Blackman-Harris window, see
+     * `Quality::new`.
+     */
+    pub fn quality(
+        &self,
+        source: crate::acquire::Source,
+        fundamental: Option<f64>,
+        harmonics: usize,
+    ) -> Result<Quality, Invalid> {
+        let spectrum = self
+            .spectrum(source, Window::BLACKMAN_HARRIS)
+            .ok_or(Invalid::EMPTY)?;
+
+        Quality::new(&spectrum, fundamental, harmonics)
+    }
+}
+
+impl crate::Redpitaya {
+    /**
+     * Generate a sine of `frequency` Hz and `amplitude` volts on `output`,
+     * capture it on `input` and analyse it.
+     *
+     * The output is stopped afterwards.
+     */
+    pub fn characterize(
+        &self,
+        output: crate::generator::Source,
+        input: crate::acquire::Source,
+        frequency: u32,
+        amplitude: f32,
+        harmonics: usize,
+    ) -> Result<Quality, crate::Error> {
+        self.generator.set_sine(output, frequency, amplitude)?;
+        self.generator.start(output);
+
+        let duration = std::time::Duration::from_secs_f64(PERIODS / f64::from(frequency));
+        let config = crate::capture::CaptureConfig {
+            sources: vec![input],
+            decimation: crate::acquire::Decimation::for_duration(duration),
+            ..Default::default()
+        };
+        let waveform = self.capture(&config);
+
+        self.generator.stop(output);
+
+        let quality = waveform?.quality(input, Some(f64::from(frequency)), harmonics)?;
+
+        Ok(quality)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    /**
+     * 1 V sine of `cycles` periods on 1024 samples, with 10 mV and 1 mV
+     * second and third harmonics and a 1 mV uniform noise.
+     */
+    fn samples(cycles: f64) -> Vec<f64> {
+        let mut seed = 42u64;
+        let mut noise = move || {
+            seed = seed
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+
+            ((seed >> 11) as f64 / (1u64 << 53) as f64 * 2. - 1.) * 1e-3
+        };
+
+        (0..1_024)
+            .map(|i| {
+                let t = std::f64::consts::TAU * cycles * i as f64 / 1_024.;
+
+                t.sin() + 0.01 * (2. * t).sin() + 0.001 * (3. * t).sin() + noise()
+            })
+            .collect()
+    }
+
+    fn spectrum(cycles: f64) -> crate::spectrum::Spectrum {
+        crate::spectrum::Spectrum::with_frequency(
+            &samples(cycles),
+            1_024.,
+            crate::spectrum::Window::BLACKMAN_HARRIS,
+        )
+    }
+
+    #[test]
+    fn test_quality() {
+        let quality = crate::spectrum::quality::Quality::new(&spectrum(31.), None, 5).unwrap();
+        let thd = 20. * (0.01f64.powi(2) + 0.001f64.powi(2)).sqrt().log10();
+
+        assert_eq!(quality.frequency, 31.);
+        assert!((quality.amplitude - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-4);
+        assert!((quality.thd - thd).abs() < 0.1);
+        assert!((quality.thd_percent() - 1.005).abs() < 0.01);
+        // Noise of 1e-3 / √3 Vrms
+        assert!((quality.snr - 61.76).abs() < 1.);
+        assert!((quality.sinad - (-thd)).abs() < 0.1);
+        assert_eq!(quality.enob, (quality.sinad - 1.76) / 6.02);
+        assert!((quality.sfdr - 40.).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_aliased_harmonics() {
+        // Second harmonic at 800, folded to 224
+        let quality =
+            crate::spectrum::quality::Quality::new(&spectrum(400.), Some(400.), 2).unwrap();
+
+        assert_eq!(quality.frequency, 400.);
+        assert!((quality.thd + 39.96).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let spectrum = crate::spectrum::Spectrum::with_frequency(
+            &[0.; 64],
+            64.,
+            crate::spectrum::Window::HANN,
+        );
+
+        assert_eq!(
+            crate::spectrum::quality::Quality::new(&spectrum, None, 5),
+            Err(crate::measure::Invalid::FLAT)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "mock")]
+    fn test_characterize() {
+        let (rx, rp) = crate::test::create_client();
+
+        assert_eq!(
+            rp.characterize(
+                crate::generator::Source::OUT1,
+                crate::acquire::Source::IN1,
+                100_000,
+                0.5,
+                5,
+            ),
+            Err(crate::Error::Timeout)
+        );
+        crate::test::assert_received(
+            &rx,
+            &[
+                "SOUR1:FUNC SINE",
+                "SOUR1:FREQ:FIX 100000",
+                "SOUR1:VOLT 0.5",
+                "SOUR1:VOLT:OFFS 0",
+                "OUTPUT1:STATE ON",
+                "ACQ:DEC 8",
+                "ACQ:DATA:UNITS VOLTS",
+                "ACQ:START",
+                "ACQ:TRIG NOW",
+                "OUTPUT1:STATE OFF",
+            ],
+        );
+    }
+}
@@ -1,6 +1,10 @@
+pub mod software;
+
+use crate::general::Board;
 use crate::socket::Socket;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Source {
     DISABLED,
     NOW,
@@ -33,6 +37,54 @@ impl std::convert::From<Source> for String {
     }
 }
 
+impl std::str::FromStr for Source {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "DISABLED" => Ok(Source::DISABLED),
+            "NOW" => Ok(Source::NOW),
+            "CH1_PE" => Ok(Source::CH1_PE),
+            "CH1_NE" => Ok(Source::CH1_NE),
+            "CH2_PE" => Ok(Source::CH2_PE),
+            "CH2_NE" => Ok(Source::CH2_NE),
+            "EXT_PE" => Ok(Source::EXT_PE),
+            "EXT_NE" => Ok(Source::EXT_NE),
+            "AWG_PE" => Ok(Source::AWG_PE),
+            "AWG_NE" => Ok(Source::AWG_NE),
+            source => Err(format!("Unknow source '{source}'")),
+        }
+    }
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", String::from(*self))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TriggerConfig {
+    pub source: Source,
+    /**
+     * Level in volts.
+     */
+    pub level: f32,
+    /**
+     * Hysteresis in volts.
+     */
+    pub hysteresis: f32,
+    /**
+     * Delay in samples.
+     */
+    pub delay: i32,
+    /**
+     * External trigger level in volts, only available on SIGNALlab 250-12.
+     */
+    pub external_level: Option<f32>,
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum State {
     WAIT,
@@ -51,6 +103,55 @@ impl std::str::FromStr for State {
     }
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    TRIGGERED,
+    TIMEOUT,
+}
+
+/**
+ * Signed trigger delay, negative values move the trigger to the end of the
+ * buffer.
+ */
+#[derive(Copy, Clone, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Delay {
+    nanos: i128,
+}
+
+impl Delay {
+    #[must_use]
+    pub fn positive(duration: std::time::Duration) -> Self {
+        Self {
+            nanos: duration.as_nanos() as i128,
+        }
+    }
+
+    #[must_use]
+    pub fn negative(duration: std::time::Duration) -> Self {
+        Self {
+            nanos: -(duration.as_nanos() as i128),
+        }
+    }
+
+    #[must_use]
+    pub fn is_negative(self) -> bool {
+        self.nanos < 0
+    }
+
+    /**
+     * Absolute value of the delay.
+     */
+    #[must_use]
+    pub fn duration(self) -> std::time::Duration {
+        let nanos = self.nanos.unsigned_abs();
+
+        std::time::Duration::new(
+            (nanos / 1_000_000_000) as u64,
+            (nanos % 1_000_000_000) as u32,
+        )
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Trigger {
     socket: Socket,
@@ -73,6 +174,15 @@ impl Trigger {
             .send(format!("ACQ:TRIG {}", Into::<String>::into(source)));
     }
 
+    /**
+     * Get the armed trigger source.
+     *
+     * The source goes back to `DISABLED` once triggered.
+     */
+    pub fn source(&self) -> Result<Source, String> {
+        self.socket.send("ACQ:TRIG:SOUR?").unwrap().parse()
+    }
+
     /**
      * Disable triggering.
      */
@@ -90,29 +200,107 @@ impl Trigger {
     }
 
     /**
-     * Set trigger delay in samples.
+     * Returns true if the buffer has been filled after the trigger.
      */
-    pub fn set_delay(&self, delay: u16) {
+    pub fn is_filled(&self) -> Result<bool, String> {
+        match self.socket.send("ACQ:TRIG:FILL?").unwrap().as_str() {
+            "1" => Ok(true),
+            "0" => Ok(false),
+            fill => Err(format!("Unknow fill state '{fill}'")),
+        }
+    }
+
+    /**
+     * Wait for the trigger then for the buffer to be filled.
+     *
+     * The state is polled with an exponential backoff, from 1 ms to 100 ms.
+     */
+    pub fn wait(&self, timeout: std::time::Duration) -> Result<Outcome, crate::Error> {
+        let deadline = std::time::Instant::now() + timeout;
+        let mut backoff = std::time::Duration::from_millis(1);
+
+        let triggered = Self::poll(deadline, &mut backoff, || Ok(self.state()? == State::TD))?
+            && Self::poll(deadline, &mut backoff, || self.is_filled())?;
+
+        if triggered {
+            Ok(Outcome::TRIGGERED)
+        } else {
+            Ok(Outcome::TIMEOUT)
+        }
+    }
+
+    fn poll<F>(
+        deadline: std::time::Instant,
+        backoff: &mut std::time::Duration,
+        ready: F,
+    ) -> Result<bool, crate::Error>
+    where
+        F: Fn() -> Result<bool, String>,
+    {
+        loop {
+            if ready()? {
+                return Ok(true);
+            }
+
+            let now = std::time::Instant::now();
+            if now >= deadline {
+                return Ok(false);
+            }
+
+            std::thread::sleep((*backoff).min(deadline - now));
+            *backoff = (*backoff * 2).min(std::time::Duration::from_millis(100));
+        }
+    }
+
+    /**
+     * Set trigger delay in samples, negative values move the trigger to the
+     * end of the buffer.
+     */
+    pub fn set_delay(&self, delay: i32) {
         self.socket.send(format!("ACQ:TRIG:DLY {delay}"));
     }
 
     /**
      * Get trigger delay in samples.
      */
-    pub fn delay(&self) -> Result<u16, <u16 as std::str::FromStr>::Err> {
+    pub fn delay(&self) -> Result<i32, <i32 as std::str::FromStr>::Err> {
         self.socket.send("ACQ:TRIG:DLY?").unwrap().parse()
     }
 
+    /**
+     * Set trigger delay as a signed duration.
+     *
+     * See `Trigger::to_samples` to convert it in samples.
+     */
+    pub fn set_delay_time(&self, delay: Delay) {
+        self.socket.send(format!("ACQ:TRIG:DLY:NS {}", delay.nanos));
+    }
+
+    /**
+     * Get trigger delay as a signed duration.
+     *
+     * See `Trigger::to_time` to convert a delay in samples.
+     */
+    pub fn delay_time(&self) -> Result<Delay, crate::Error> {
+        let response = self.socket.send("ACQ:TRIG:DLY:NS?").unwrap_or_default();
+
+        parse_duration(&response)
+    }
+
     /**
      * Set trigger delay in ns.
      */
+    #[deprecated(note = "use `Trigger::set_delay_time`")]
     pub fn set_delay_in_ns(&self, delay: u8) {
-        self.socket.send(format!("ACQ:TRIG:DLY:NS {delay}"));
+        self.set_delay_time(Delay::positive(std::time::Duration::from_nanos(
+            delay.into(),
+        )));
     }
 
     /**
      * Get trigger delay in ns.
      */
+    #[deprecated(note = "use `Trigger::delay_time`")]
     pub fn delay_in_ns(&self) -> Result<u8, <u8 as std::str::FromStr>::Err> {
         self.socket
             .send("ACQ:TRIG:DLY:NS?")
@@ -121,6 +309,39 @@ impl Trigger {
             .parse()
     }
 
+    /**
+     * Convert a delay in samples to a duration, using the current decimation.
+     */
+    pub fn to_time(&self, samples: i32) -> Result<Delay, crate::Error> {
+        let decimation: crate::acquire::Decimation = self.socket.query("ACQ:DEC?")?;
+        let duration = decimation.duration(samples.unsigned_abs());
+
+        Ok(if samples < 0 {
+            Delay::negative(duration)
+        } else {
+            Delay::positive(duration)
+        })
+    }
+
+    /**
+     * Convert a delay to samples, using the current decimation.
+     */
+    pub fn to_samples(&self, delay: Delay) -> Result<i32, crate::Error> {
+        let decimation: crate::acquire::Decimation = self.socket.query("ACQ:DEC?")?;
+        let samples = decimation.samples(delay.duration())?;
+        let samples = i32::try_from(samples).map_err(|_| crate::Error::OutOfRange {
+            value: delay.nanos as f64,
+            min: f64::from(i32::MIN) * decimation.duration(1).as_nanos() as f64,
+            max: f64::from(i32::MAX) * decimation.duration(1).as_nanos() as f64,
+        })?;
+
+        Ok(if delay.is_negative() {
+            -samples
+        } else {
+            samples
+        })
+    }
+
     /**
      * Sets the trigger threshold hysteresis value in volts.
      *
@@ -133,27 +354,132 @@ impl Trigger {
     /**
      * Gets currently set trigger threshold hysteresis value in volts.
      */
-    pub fn hysteresis(&self) -> Result<f32, <f32 as std::str::FromStr>::Err> {
-        self.socket.send("ACQ:TRIG:HYST?").unwrap().parse()
+    pub fn hysteresis(&self) -> Result<f32, crate::Error> {
+        parse_volts(&self.socket.send("ACQ:TRIG:HYST?").unwrap())
     }
 
     /**
-     * Set trigger level in mV.
+     * Set trigger level in volts.
      */
     pub fn set_level(&self, level: f32) {
         self.socket.send(format!("ACQ:TRIG:LEV {level}"));
     }
 
     /**
-     * Get trigger level in mV.
+     * Get trigger level in volts.
      */
-    pub fn level(&self) -> Result<f32, <f32 as std::str::FromStr>::Err> {
-        self.socket
-            .send("ACQ:TRIG:LEV?")
-            .unwrap()
-            .replace("mV", "")
-            .parse()
+    pub fn level(&self) -> Result<f32, crate::Error> {
+        parse_volts(&self.socket.send("ACQ:TRIG:LEV?").unwrap())
     }
+
+    /**
+     * Set external trigger level in volts, only available on SIGNALlab
+     * 250-12.
+     */
+    pub fn set_external_level(&self, level: f32) -> Result<(), crate::Error> {
+        self.check_external_level()?;
+
+        self.socket.send(format!("ACQ:TRIG:EXT:LEV {level}"));
+
+        Ok(())
+    }
+
+    /**
+     * Get external trigger level in volts, only available on SIGNALlab
+     * 250-12.
+     */
+    pub fn external_level(&self) -> Result<f32, crate::Error> {
+        self.check_external_level()?;
+
+        parse_volts(&self.socket.send("ACQ:TRIG:EXT:LEV?").unwrap())
+    }
+
+    fn check_external_level(&self) -> Result<(), crate::Error> {
+        match crate::general::board(&self.socket)? {
+            Board::SIGNALLAB_250_12 => Ok(()),
+            board => Err(crate::Error::Unsupported(board)),
+        }
+    }
+
+    /**
+     * Read the full trigger configuration.
+     */
+    pub fn config(&self) -> Result<TriggerConfig, crate::Error> {
+        let external_level = if crate::general::board(&self.socket)? == Board::SIGNALLAB_250_12 {
+            Some(self.external_level()?)
+        } else {
+            None
+        };
+
+        Ok(TriggerConfig {
+            source: self.source()?,
+            level: self.level()?,
+            hysteresis: self.hysteresis()?,
+            delay: self.delay()?,
+            external_level,
+        })
+    }
+
+    /**
+     * Set the full trigger configuration, the source is armed last.
+     */
+    pub fn configure(&self, config: &TriggerConfig) -> Result<(), crate::Error> {
+        if let Some(level) = config.external_level {
+            self.set_external_level(level)?;
+        }
+
+        self.set_level(config.level);
+        self.set_hysteresis(config.hysteresis);
+        self.set_delay(config.delay);
+        self.enable(config.source);
+
+        Ok(())
+    }
+}
+
+/**
+ * Parse a voltage, in volts without unit or with a `V` or `mV` suffix.
+ */
+fn parse_volts(value: &str) -> Result<f32, crate::Error> {
+    let value = value.trim();
+
+    let volts = if let Some(value) = value.strip_suffix("mV") {
+        value.trim().parse::<f32>()? / 1_000.
+    } else {
+        value.trim_end_matches('V').trim().parse()?
+    };
+
+    Ok(volts)
+}
+
+/**
+ * Parse a signed duration, in ns without unit or with a `ns`, `us`, `ms` or
+ * `s` suffix.
+ */
+fn parse_duration(value: &str) -> Result<Delay, crate::Error> {
+    let value = value.trim();
+    let invalid = || crate::Error::Response(value.to_owned());
+    // The unit is the alphabetic suffix, an exponent is part of the number
+    let number = value.trim_end_matches(|c: char| c.is_ascii_alphabetic());
+    let unit = &value[number.len()..];
+    let number: f64 = number.trim().parse().map_err(|_| invalid())?;
+
+    let scale = match unit {
+        "" | "ns" => 1e-9,
+        "us" => 1e-6,
+        "ms" => 1e-3,
+        "s" => 1.,
+        _ => return Err(invalid()),
+    };
+
+    let duration =
+        std::time::Duration::try_from_secs_f64((number * scale).abs()).map_err(|_| invalid())?;
+
+    Ok(if number < 0. {
+        Delay::negative(duration)
+    } else {
+        Delay::positive(duration)
+    })
 }
 
 #[cfg(test)]
@@ -175,6 +501,104 @@ mod test {
         assert_eq!("ACQ:TRIG DISABLED\r\n", rx.recv().unwrap());
     }
 
+    #[test]
+    fn test_is_filled() {
+        let (_, rp) = crate::test::create_client();
+
+        assert!(rp.trigger.is_filled().is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "mock")]
+    fn test_wait() {
+        let (rx, rp) = crate::test::create_client();
+
+        let timeout = std::time::Duration::from_millis(20);
+        let start = std::time::Instant::now();
+
+        assert_eq!(
+            rp.trigger.wait(timeout),
+            Ok(crate::trigger::Outcome::TIMEOUT)
+        );
+        assert!(start.elapsed() >= timeout);
+
+        rp.trigger.enable(crate::trigger::Source::EXT_PE);
+        assert_eq!("ACQ:TRIG EXT_PE\r\n", rx.recv().unwrap());
+
+        assert_eq!(
+            rp.trigger.wait(std::time::Duration::from_secs(1)),
+            Ok(crate::trigger::Outcome::TRIGGERED)
+        );
+    }
+
+    #[test]
+    fn test_source() {
+        let sources = [
+            crate::trigger::Source::DISABLED,
+            crate::trigger::Source::NOW,
+            crate::trigger::Source::CH1_PE,
+            crate::trigger::Source::CH1_NE,
+            crate::trigger::Source::CH2_PE,
+            crate::trigger::Source::CH2_NE,
+            crate::trigger::Source::EXT_PE,
+            crate::trigger::Source::EXT_NE,
+            crate::trigger::Source::AWG_PE,
+            crate::trigger::Source::AWG_NE,
+        ];
+
+        for source in sources {
+            assert_eq!(source.to_string().parse(), Ok(source));
+        }
+
+        let (_, rp) = crate::test::create_client();
+
+        #[cfg(feature = "mock")]
+        assert_eq!(rp.trigger.source(), Ok(crate::trigger::Source::CH1_PE));
+
+        #[cfg(not(feature = "mock"))]
+        assert!(rp.trigger.source().is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "mock")]
+    fn test_config() {
+        let (rx, rp) = crate::test::create_client();
+
+        let config = crate::trigger::TriggerConfig {
+            source: crate::trigger::Source::CH1_PE,
+            level: 0.4,
+            hysteresis: 0.75,
+            delay: 2314,
+            external_level: None,
+        };
+        assert_eq!(rp.trigger.config(), Ok(config));
+
+        rp.trigger.configure(&config).unwrap();
+        crate::test::assert_received(
+            &rx,
+            &[
+                "ACQ:TRIG:LEV 0.4",
+                "ACQ:TRIG:HYST 0.75",
+                "ACQ:TRIG:DLY 2314",
+                "ACQ:TRIG CH1_PE",
+            ],
+        );
+
+        let config = crate::trigger::TriggerConfig {
+            external_level: Some(1.5),
+            ..config
+        };
+        assert_eq!(
+            rp.trigger.configure(&config),
+            Err(crate::Error::Unsupported(
+                crate::general::Board::STEMLAB_125_14
+            ))
+        );
+
+        rp.set_board(crate::general::Board::SIGNALLAB_250_12);
+        assert_eq!(rp.trigger.config(), Ok(config));
+    }
+
     #[test]
     fn test_delay() {
         let (rx, rp) = crate::test::create_client();
@@ -186,13 +610,99 @@ mod test {
     }
 
     #[test]
+    fn test_delay_time() {
+        let (rx, rp) = crate::test::create_client();
+
+        let delay = crate::trigger::Delay::negative(std::time::Duration::from_nanos(128));
+
+        rp.trigger.set_delay_time(delay);
+        assert_eq!("ACQ:TRIG:DLY:NS -128\r\n", rx.recv().unwrap());
+
+        #[cfg(feature = "mock")]
+        assert_eq!(rp.trigger.delay_time(), Ok(delay));
+
+        #[cfg(not(feature = "mock"))]
+        assert!(rp.trigger.delay_time().is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "mock")]
+    fn test_delay_conversion() {
+        let (_, rp) = crate::test::create_client();
+
+        let delay = crate::trigger::Delay::negative(std::time::Duration::from_nanos(800));
+        assert_eq!(rp.trigger.to_time(-100), Ok(delay));
+        assert_eq!(rp.trigger.to_samples(delay), Ok(-100));
+        assert_eq!(
+            rp.trigger.to_samples(crate::trigger::Delay::positive(
+                std::time::Duration::from_micros(1)
+            )),
+            Ok(125)
+        );
+        assert_eq!(
+            rp.trigger.to_samples(crate::trigger::Delay::positive(
+                std::time::Duration::from_secs(20)
+            )),
+            Err(crate::Error::OutOfRange {
+                value: 20_000_000_000.,
+                min: f64::from(i32::MIN) * 8.,
+                max: f64::from(i32::MAX) * 8.,
+            })
+        );
+    }
+
+    #[test]
+    #[allow(deprecated)]
     fn test_delay_in_ns() {
         let (rx, rp) = crate::test::create_client();
 
         rp.trigger.set_delay_in_ns(128);
         assert_eq!("ACQ:TRIG:DLY:NS 128\r\n", rx.recv().unwrap());
+    }
+
+    #[test]
+    fn test_negative_delay() {
+        let (rx, rp) = crate::test::create_client();
+
+        rp.trigger.set_delay(-100_000);
+        assert_eq!("ACQ:TRIG:DLY -100000\r\n", rx.recv().unwrap());
+    }
 
-        assert_eq!(rp.trigger.delay_in_ns(), Ok(128));
+    #[test]
+    fn test_parse_volts() {
+        assert_eq!(crate::trigger::parse_volts("400mV"), Ok(0.4));
+        assert_eq!(crate::trigger::parse_volts("0.4 V"), Ok(0.4));
+        assert_eq!(crate::trigger::parse_volts("0.4"), Ok(0.4));
+        assert!(crate::trigger::parse_volts("ERR!").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        let nanos = |nanos| crate::trigger::Delay::positive(std::time::Duration::from_nanos(nanos));
+
+        assert_eq!(crate::trigger::parse_duration("1.5us"), Ok(nanos(1_500)));
+        assert_eq!(crate::trigger::parse_duration("1.5e3ns"), Ok(nanos(1_500)));
+        assert_eq!(crate::trigger::parse_duration("128"), Ok(nanos(128)));
+        assert_eq!(
+            crate::trigger::parse_duration("-128ns"),
+            Ok(crate::trigger::Delay::negative(
+                std::time::Duration::from_nanos(128)
+            ))
+        );
+        assert_eq!(
+            crate::trigger::parse_duration("1e3s"),
+            Ok(crate::trigger::Delay::positive(
+                std::time::Duration::from_secs(1_000)
+            ))
+        );
+        assert_eq!(
+            crate::trigger::parse_duration("1e30s"),
+            Err(crate::Error::Response("1e30s".to_owned()))
+        );
+        assert_eq!(
+            crate::trigger::parse_duration("128 hours"),
+            Err(crate::Error::Response("128 hours".to_owned()))
+        );
     }
 
     #[test]
@@ -0,0 +1,467 @@
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Edge {
+    RISING,
+    FALLING,
+    EITHER,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Polarity {
+    POSITIVE,
+    NEGATIVE,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Width {
+    LESS(usize),
+    GREATER(usize),
+}
+
+impl Width {
+    fn matches(self, width: usize) -> bool {
+        match self {
+            Width::LESS(max) => width < max,
+            Width::GREATER(min) => width > min,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Window {
+    ENTER,
+    EXIT,
+}
+
+/**
+ * Post-capture trigger condition.
+ *
+ * Durations are in samples, see `Waveform::interval` to convert them.
+ */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Condition {
+    /**
+     * Crossing of `level`, the signal must go past `level ± hysteresis` to
+     * re-arm the trigger.
+     */
+    EDGE {
+        level: f64,
+        hysteresis: f64,
+        edge: Edge,
+    },
+    /**
+     * Pulse, measured at `level`, which width matches. Triggers on the pulse
+     * end.
+     */
+    PULSE_WIDTH {
+        level: f64,
+        hysteresis: f64,
+        polarity: Polarity,
+        width: Width,
+    },
+    /**
+     * Pulse crossing `low` but going back before reaching `high` (reversed
+     * for negative polarity). Triggers on the pulse end.
+     */
+    RUNT {
+        low: f64,
+        high: f64,
+        polarity: Polarity,
+    },
+    /**
+     * Signal entering or leaving the `[low, high]` window.
+     */
+    WINDOW { low: f64, high: f64, window: Window },
+    /**
+     * Transition between `low` and `high` which duration matches. Triggers
+     * when the transition ends.
+     */
+    SLEW_RATE {
+        low: f64,
+        high: f64,
+        edge: Edge,
+        width: Width,
+    },
+}
+
+impl Condition {
+    /**
+     * Returns indices where the condition triggers.
+     */
+    #[must_use]
+    pub fn find(&self, samples: &[f64]) -> Vec<usize> {
+        match *self {
+            Condition::EDGE {
+                level,
+                hysteresis,
+                edge,
+            } => edges(samples, level, hysteresis)
+                .into_iter()
+                .filter(|(_, e)| edge == Edge::EITHER || edge == *e)
+                .map(|(index, _)| index)
+                .collect(),
+            Condition::PULSE_WIDTH {
+                level,
+                hysteresis,
+                polarity,
+                width,
+            } => {
+                let start = match polarity {
+                    Polarity::POSITIVE => Edge::RISING,
+                    Polarity::NEGATIVE => Edge::FALLING,
+                };
+
+                edges(samples, level, hysteresis)
+                    .windows(2)
+                    .filter(|w| w[0].1 == start && width.matches(w[1].0 - w[0].0))
+                    .map(|w| w[1].0)
+                    .collect()
+            }
+            Condition::RUNT {
+                low,
+                high,
+                polarity,
+            } => {
+                let (low, high, sign) = match polarity {
+                    Polarity::POSITIVE => (low, high, 1.),
+                    Polarity::NEGATIVE => (-high, -low, -1.),
+                };
+
+                runts(samples.iter().map(|x| sign * x), low, high)
+            }
+            Condition::WINDOW { low, high, window } => {
+                let inside = |x: f64| (low..=high).contains(&x);
+
+                samples
+                    .windows(2)
+                    .enumerate()
+                    .filter(|(_, w)| match window {
+                        Window::ENTER => !inside(w[0]) && inside(w[1]),
+                        Window::EXIT => inside(w[0]) && !inside(w[1]),
+                    })
+                    .map(|(index, _)| index + 1)
+                    .collect()
+            }
+            Condition::SLEW_RATE {
+                low,
+                high,
+                edge,
+                width,
+            } => {
+                let mut indices = Vec::new();
+
+                if edge != Edge::FALLING {
+                    indices.extend(transitions(samples.iter().copied(), low, high, width));
+                }
+                if edge != Edge::RISING {
+                    indices.extend(transitions(samples.iter().map(|x| -x), -high, -low, width));
+                }
+
+                indices.sort_unstable();
+                indices
+            }
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Level {
+    /**
+     * Above the threshold.
+     */
+    HIGH(f64),
+    /**
+     * Below the threshold.
+     */
+    LOW(f64),
+    ANY,
+}
+
+impl Level {
+    fn matches(self, x: f64) -> bool {
+        match self {
+            Level::HIGH(threshold) => x > threshold,
+            Level::LOW(threshold) => x < threshold,
+            Level::ANY => true,
+        }
+    }
+}
+
+/**
+ * Two channels pattern, triggers when both levels start matching.
+ */
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Pattern(pub Level, pub Level);
+
+impl Pattern {
+    #[must_use]
+    pub fn find(&self, a: &[f64], b: &[f64]) -> Vec<usize> {
+        let mut indices = Vec::new();
+        let mut previous = true;
+
+        for (index, (a, b)) in a.iter().zip(b.iter()).enumerate() {
+            let matches = self.0.matches(*a) && self.1.matches(*b);
+
+            if matches && !previous {
+                indices.push(index);
+            }
+
+            previous = matches;
+        }
+
+        indices
+    }
+}
+
+/**
+ * Shift each capture so its first trigger is at `position`, captures
+ * without trigger are dropped. Missing samples are `NaN`.
+ */
+#[must_use]
+pub fn align(captures: &[Vec<f64>], condition: &Condition, position: usize) -> Vec<Vec<f64>> {
+    captures
+        .iter()
+        .filter_map(|samples| {
+            let trigger = *condition.find(samples).first()?;
+
+            let aligned = (0..samples.len())
+                .map(|index| {
+                    (index + trigger)
+                        .checked_sub(position)
+                        .and_then(|index| samples.get(index))
+                        .copied()
+                        .unwrap_or(f64::NAN)
+                })
+                .collect();
+
+            Some(aligned)
+        })
+        .collect()
+}
+
+/**
+ * Level crossings with hysteresis.
+ */
+fn edges(samples: &[f64], level: f64, hysteresis: f64) -> Vec<(usize, Edge)> {
+    let mut edges = Vec::new();
+    let mut rising_armed = false;
+    let mut falling_armed = false;
+
+    for (index, x) in samples.iter().enumerate() {
+        if rising_armed && *x >= level {
+            edges.push((index, Edge::RISING));
+            rising_armed = false;
+        } else if falling_armed && *x <= level {
+            edges.push((index, Edge::FALLING));
+            falling_armed = false;
+        }
+
+        if *x < level - hysteresis {
+            rising_armed = true;
+        }
+        if *x > level + hysteresis {
+            falling_armed = true;
+        }
+    }
+
+    edges
+}
+
+fn runts<I>(samples: I, low: f64, high: f64) -> Vec<usize>
+where
+    I: Iterator<Item = f64>,
+{
+    let mut indices = Vec::new();
+    let mut previous = f64::NAN;
+    let mut in_pulse = false;
+    let mut reached_high = false;
+
+    for (index, x) in samples.enumerate() {
+        if previous < low && x >= low {
+            in_pulse = true;
+            reached_high = false;
+        }
+
+        if in_pulse && x >= high {
+            reached_high = true;
+        }
+
+        if in_pulse && x < low {
+            if !reached_high {
+                indices.push(index);
+            }
+
+            in_pulse = false;
+        }
+
+        previous = x;
+    }
+
+    indices
+}
+
+fn transitions<I>(samples: I, low: f64, high: f64, width: Width) -> Vec<usize>
+where
+    I: Iterator<Item = f64>,
+{
+    let mut indices = Vec::new();
+    let mut start = None;
+    let mut previous = f64::NAN;
+
+    for (index, x) in samples.enumerate() {
+        if previous < low && x >= low {
+            start = Some(index);
+        } else if x < low {
+            start = None;
+        }
+
+        if let Some(first) = start
+            && previous < high
+            && x >= high
+        {
+            if width.matches(index - first) {
+                indices.push(index);
+            }
+
+            start = None;
+        }
+
+        previous = x;
+    }
+
+    indices
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SIGNAL: [f64; 12] = [0., 0., 1., 1., 1., 0., 0., 1., 0., 0.5, 0., 0.];
+
+    #[test]
+    fn test_edge() {
+        let rising = Condition::EDGE {
+            level: 0.5,
+            hysteresis: 0.1,
+            edge: Edge::RISING,
+        };
+        assert_eq!(rising.find(&SIGNAL), vec![2, 7, 9]);
+
+        let either = Condition::EDGE {
+            level: 0.5,
+            hysteresis: 0.1,
+            edge: Edge::EITHER,
+        };
+        assert_eq!(either.find(&SIGNAL), vec![2, 5, 7, 8, 9]);
+
+        let noisy = [0., 0.6, 0.45, 0.6, 1., 0.];
+        assert_eq!(rising.find(&noisy), vec![1]);
+    }
+
+    #[test]
+    fn test_pulse_width() {
+        let condition = Condition::PULSE_WIDTH {
+            level: 0.5,
+            hysteresis: 0.1,
+            polarity: Polarity::POSITIVE,
+            width: Width::GREATER(2),
+        };
+        assert_eq!(condition.find(&SIGNAL), vec![5]);
+
+        let condition = Condition::PULSE_WIDTH {
+            level: 0.5,
+            hysteresis: 0.1,
+            polarity: Polarity::POSITIVE,
+            width: Width::LESS(2),
+        };
+        assert_eq!(condition.find(&SIGNAL), vec![8]);
+    }
+
+    #[test]
+    fn test_runt() {
+        let condition = Condition::RUNT {
+            low: 0.2,
+            high: 0.8,
+            polarity: Polarity::POSITIVE,
+        };
+        assert_eq!(condition.find(&SIGNAL), vec![10]);
+
+        let condition = Condition::RUNT {
+            low: -0.8,
+            high: -0.2,
+            polarity: Polarity::NEGATIVE,
+        };
+        assert_eq!(condition.find(&[0., -0.5, 0., -1., 0.]), vec![2]);
+    }
+
+    #[test]
+    fn test_window() {
+        let condition = Condition::WINDOW {
+            low: 0.2,
+            high: 0.8,
+            window: Window::ENTER,
+        };
+        assert_eq!(condition.find(&SIGNAL), vec![9]);
+
+        let condition = Condition::WINDOW {
+            low: 0.2,
+            high: 0.8,
+            window: Window::EXIT,
+        };
+        assert_eq!(condition.find(&SIGNAL), vec![10]);
+    }
+
+    #[test]
+    fn test_slew_rate() {
+        let signal = [0., 0.25, 0.5, 0.75, 1., 0., 1., 0.5, 0.];
+
+        let condition = Condition::SLEW_RATE {
+            low: 0.2,
+            high: 0.8,
+            edge: Edge::RISING,
+            width: Width::GREATER(1),
+        };
+        assert_eq!(condition.find(&signal), vec![4]);
+
+        let condition = Condition::SLEW_RATE {
+            low: 0.2,
+            high: 0.8,
+            edge: Edge::EITHER,
+            width: Width::LESS(2),
+        };
+        assert_eq!(condition.find(&signal), vec![5, 6, 8]);
+    }
+
+    #[test]
+    fn test_pattern() {
+        let pattern = Pattern(Level::HIGH(0.5), Level::LOW(0.5));
+
+        assert_eq!(
+            pattern.find(&[1., 1., 0., 1., 1.], &[1., 0., 0., 0., 1.]),
+            vec![1, 3]
+        );
+    }
+
+    #[test]
+    fn test_align() {
+        let condition = Condition::EDGE {
+            level: 0.5,
+            hysteresis: 0.1,
+            edge: Edge::RISING,
+        };
+
+        let aligned = align(
+            &[
+                vec![0., 0., 1., 1.],
+                vec![0., 1., 1., 1.],
+                vec![0., 0., 0., 0.],
+            ],
+            &condition,
+            2,
+        );
+
+        assert_eq!(aligned.len(), 2);
+        assert_eq!(aligned[0], vec![0., 0., 1., 1.]);
+        assert!(aligned[1][0].is_nan());
+        assert_eq!(aligned[1][1..], [0., 1., 1.]);
+    }
+}
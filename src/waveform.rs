@@ -0,0 +1,206 @@
+use crate::acquire::{Decimation, Gain, SamplingRate, Source};
+use crate::data::Unit;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Channel {
+    pub source: Source,
+    pub gain: Gain,
+    pub samples: Vec<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Waveform {
+    pub channels: Vec<Channel>,
+    pub decimation: Decimation,
+    /**
+     * Index of the trigger in samples, may be outside of the samples after
+     * slicing.
+     */
+    pub trigger: isize,
+    pub units: Unit,
+    /**
+     * Host time of the capture.
+     */
+    pub timestamp: std::time::SystemTime,
+}
+
+impl Waveform {
+    #[must_use]
+    pub fn sampling_rate(&self) -> SamplingRate {
+        self.decimation.into()
+    }
+
+    /**
+     * Time between two samples, in seconds.
+     */
+    #[must_use]
+    pub fn interval(&self) -> f64 {
+        1. / self.sampling_rate().frequency()
+    }
+
+    /**
+     * Number of samples per channel.
+     */
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.channels
+            .iter()
+            .map(|channel| channel.samples.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /**
+     * Time of the sample `index` relative to the trigger, in seconds.
+     */
+    #[must_use]
+    pub fn time(&self, index: usize) -> f64 {
+        (index as isize - self.trigger) as f64 * self.interval()
+    }
+
+    /**
+     * Time axis relative to the trigger, in seconds.
+     */
+    #[must_use]
+    pub fn times(&self) -> Vec<f64> {
+        (0..self.len()).map(|index| self.time(index)).collect()
+    }
+
+    #[must_use]
+    pub fn channel(&self, source: Source) -> Option<&Channel> {
+        self.channels
+            .iter()
+            .find(|channel| channel.source == source)
+    }
+
+    /**
+     * `(time, value)` pairs of a channel, time relative to the trigger.
+     */
+    #[must_use]
+    pub fn points(&self, source: Source) -> Option<Vec<(f64, f64)>> {
+        self.channel(source).map(|channel| {
+            channel
+                .samples
+                .iter()
+                .enumerate()
+                .map(|(index, value)| (self.time(index), *value))
+                .collect()
+        })
+    }
+
+    /**
+     * Keep samples between `start` and `end` seconds, relative to the
+     * trigger.
+     */
+    #[must_use]
+    pub fn slice(&self, start: f64, end: f64) -> Self {
+        let index = |time: f64| {
+            let samples = time / self.interval();
+            // A whole number of samples may be a bit above after the division
+            let samples = if (samples - samples.round()).abs() < 1e-9 {
+                samples.round()
+            } else {
+                samples.ceil()
+            };
+            let index = samples as isize + self.trigger;
+
+            index.clamp(0, self.len() as isize) as usize
+        };
+        let (first, last) = (index(start), index(end).max(index(start)));
+
+        let channels = self
+            .channels
+            .iter()
+            .map(|channel| Channel {
+                samples: channel.samples
+                    [first.min(channel.samples.len())..last.min(channel.samples.len())]
+                    .to_vec(),
+                ..channel.clone()
+            })
+            .collect();
+
+        Self {
+            channels,
+            trigger: self.trigger - first as isize,
+            ..self.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    fn waveform() -> crate::waveform::Waveform {
+        crate::waveform::Waveform {
+            channels: vec![
+                crate::test::channel(crate::acquire::Source::IN1, vec![0., 1., 2., 3., 4.]),
+                crate::waveform::Channel {
+                    gain: crate::acquire::Gain::HV,
+                    ..crate::test::channel(
+                        crate::acquire::Source::IN2,
+                        vec![0., -1., -2., -3., -4.],
+                    )
+                },
+            ],
+            decimation: crate::acquire::Decimation::DEC_8,
+            trigger: 2,
+            ..crate::test::waveform(Vec::new())
+        }
+    }
+
+    #[test]
+    fn test_time() {
+        let waveform = waveform();
+
+        assert_eq!(waveform.interval(), 64e-9);
+        assert_eq!(waveform.time(0), -128e-9);
+        assert_eq!(waveform.time(2), 0.);
+        assert_eq!(waveform.times().len(), 5);
+    }
+
+    #[test]
+    fn test_channel() {
+        let waveform = waveform();
+
+        assert_eq!(
+            waveform
+                .channel(crate::acquire::Source::IN2)
+                .map(|channel| channel.gain),
+            Some(crate::acquire::Gain::HV)
+        );
+        assert_eq!(
+            waveform.points(crate::acquire::Source::IN1).unwrap()[3],
+            (64e-9, 3.)
+        );
+    }
+
+    #[test]
+    fn test_slice() {
+        let waveform = waveform().slice(0., 128e-9);
+
+        assert_eq!(waveform.trigger, 0);
+        assert_eq!(waveform.channels[0].samples, vec![2., 3.]);
+        assert_eq!(waveform.channels[1].samples, vec![-2., -3.]);
+
+        let waveform = waveform.slice(64e-9, 1.);
+        assert_eq!(waveform.trigger, -1);
+        assert_eq!(waveform.channels[0].samples, vec![3.]);
+    }
+
+    #[test]
+    fn test_slice_rounding() {
+        let waveform = crate::waveform::Waveform {
+            decimation: crate::acquire::Decimation::DEC_8,
+            ..crate::test::waveform(vec![0.; 20])
+        };
+
+        // 15 × 64 ns is 15.000000000000002 intervals of 1 / 15.625 MHz
+        assert_eq!(waveform.slice(0., 15. * 64e-9).len(), 15);
+        assert_eq!(waveform.slice(15. * 64e-9, 1.).len(), 5);
+        assert_eq!(waveform.slice(0., 14.5 * 64e-9).len(), 15);
+    }
+}